@@ -9,7 +9,8 @@ use super::CMDOPT;
 use super::cmdline_output::*;
 use colored::Colorize;
 use noita_save_manager_core::{
-    Core, NSBoolResult, NSResult, ResultExt, output_manager::OutputManager, throw,
+    AutoSaveEvent, Core, LaunchMode, LiveStatus, NSBoolResult, NSComResult, NSResult, RestoreMode,
+    ResultExt, output_manager::OutputManager, throw,
 };
 use regex::Regex;
 use rustyline::ExternalPrinter;
@@ -40,9 +41,11 @@ pub struct CommandParser<'a> {
 
 impl<'a> CommandParser<'a> {
     pub fn new() -> NSResult<Self> {
+        let mut core = Core::new(CMDOPT)?;
+        core.report_startup_warnings();
         let mut new = Self {
             commands: Vec::new(),
-            m_core: Arc::new(Mutex::new(Core::new(CMDOPT)?)),
+            m_core: Arc::new(Mutex::new(core)),
             m_ssave_kit: Arc::new(Mutex::new(SsaveKit {
                 m_ssave_thread: None,
                 m_auto_save_flag: Arc::new(Mutex::new(false)),
@@ -148,6 +151,12 @@ impl<'a> CommandParser<'a> {
             &t!("man.qdelete"),
             Self::quick_delete,
         );
+        new.add_command(
+            &["prune", "pr"],
+            &t!("exp.prune"),
+            &t!("man.prune"),
+            Self::prune,
+        );
 
         // LOCK
         new.add_command(
@@ -170,6 +179,54 @@ impl<'a> CommandParser<'a> {
             &t!("man.usage"),
             Self::usage,
         );
+        new.add_command(
+            &["config", "cfg"],
+            &t!("exp.config"),
+            &t!("man.config"),
+            Self::config,
+        );
+        new.add_command(
+            &["inspect", "ins"],
+            &t!("exp.inspect"),
+            &t!("man.inspect"),
+            Self::inspect,
+        );
+        new.add_command(
+            &["stats", "stat"],
+            &t!("exp.stats"),
+            &t!("man.stats"),
+            Self::stats,
+        );
+        new.add_command(
+            &["diff", "df"],
+            &t!("exp.diff"),
+            &t!("man.diff"),
+            Self::diff,
+        );
+        new.add_command(
+            &["modprofile", "mp"],
+            &t!("exp.modprofile"),
+            &t!("man.modprofile"),
+            Self::mod_profile,
+        );
+        new.add_command(
+            &["progress", "pg"],
+            &t!("exp.progress"),
+            &t!("man.progress"),
+            Self::progress_profile,
+        );
+        new.add_command(
+            &["shared", "sh"],
+            &t!("exp.shared"),
+            &t!("man.shared"),
+            Self::shared_settings,
+        );
+        new.add_command(
+            &["paths", "pa"],
+            &t!("exp.paths"),
+            &t!("man.paths"),
+            Self::paths,
+        );
         Ok(new)
     }
 
@@ -262,6 +319,11 @@ impl<'a> CommandParser<'a> {
     }
 
     pub fn cls(&self) {
+        let core = self.m_core.lock().ok();
+        self.draw_banner(core.as_deref());
+    }
+
+    fn draw_banner(&self, core: Option<&CmdCore>) {
         #[cfg(target_os = "linux")]
         std::process::Command::new("clear").status().unwrap();
         #[cfg(target_os = "windows")]
@@ -283,32 +345,57 @@ impl<'a> CommandParser<'a> {
             print_with_pad(&item.cmd_explanation, 22);
             #[cfg(target_os = "windows")]
             match index + 1 {
-                3 | 8 | 11 | 14 | 16 => println!(),
-                5 | 9 | 18 => println!("\n"),
+                3 | 8 | 11 | 14 | 17 | 22 => println!(),
+                5 | 9 | 19 => println!("\n"),
                 _ => (),
             }
             #[cfg(target_os = "linux")]
             match index + 1 {
-                3 | 7 | 10 | 13 | 15 => println!(),
-                4 | 8 | 17 => println!("\n"),
+                3 | 7 | 10 | 13 | 16 | 21 => println!(),
+                4 | 8 | 18 => println!("\n"),
                 _ => (),
             }
         }
         CMDOPT.log_green(format!("\n{:=^120}\n", ""));
+        if let Some(core) = core {
+            Self::print_live_status(core);
+        }
         CmdlineOutput::flush();
     }
 
-    fn clear(&self, _core: &mut CmdCore, _parameter: Vec<String>) -> NSBoolResult {
-        self.cls();
+    fn print_live_status(core: &CmdCore) {
+        match core.live_status() {
+            Ok(status @ LiveStatus::InSync { .. }) => CMDOPT.log_green(format!("{}\n", status)),
+            Ok(status @ LiveStatus::ModifiedSince { .. }) => {
+                CMDOPT.log(format!("{}\n", status).bright_yellow().to_string())
+            }
+            Ok(status) => CMDOPT.log(format!("{}\n", status)),
+            Err(e) => CMDOPT.warning(
+                t!("warn.fail_get_live_status").to_string()
+                    + &format!(": {}\n", e.get_explanation().join(", ")),
+            ),
+        }
+    }
+
+    fn clear(&self, core: &mut CmdCore, _parameter: Vec<String>) -> NSBoolResult {
+        self.draw_banner(Some(core));
         Ok(true)
     }
 
-    fn quit(&self, _core: &mut CmdCore, _parameter: Vec<String>) -> NSBoolResult {
+    fn quit(&self, core: &mut CmdCore, _parameter: Vec<String>) -> NSBoolResult {
+        Self::print_live_status(core);
         Ok(false)
     }
 
-    fn startgame(&self, core: &mut CmdCore, _parameter: Vec<String>) -> NSBoolResult {
-        core.startgame()?;
+    fn startgame(&self, core: &mut CmdCore, parameter: Vec<String>) -> NSBoolResult {
+        let mode = match Self::option_value(&parameter, &["-m", "--mode"]) {
+            Some(mode) => match LaunchMode::parse(mode) {
+                Some(mode) => Some(mode),
+                None => return throw(&t!("warn.invalid_launch_mode")),
+            },
+            None => None,
+        };
+        core.startgame(mode)?;
         Ok(true)
     }
 
@@ -329,6 +416,10 @@ impl<'a> CommandParser<'a> {
     }
 
     fn save(&self, core: &mut CmdCore, mut parameter: Vec<String>) -> NSBoolResult {
+        let with_shared = parameter
+            .iter()
+            .any(|item| item == "--shared" || item == "-S");
+        parameter.retain(|item| item != "--shared" && item != "-S");
         let name = if parameter.is_empty() {
             CMDOPT.input(t!("prompt.save_name").to_string())?
         } else {
@@ -343,7 +434,7 @@ impl<'a> CommandParser<'a> {
         } else {
             parameter.remove(0)
         };
-        core.save(name, note)?;
+        core.save(name, note, with_shared)?;
 
         CMDOPT.succeed();
         Ok(true)
@@ -364,7 +455,7 @@ impl<'a> CommandParser<'a> {
         Ok(true)
     }
 
-    fn auto_save(&self, _core: &mut CmdCore, mut parameter: Vec<String>) -> NSBoolResult {
+    fn auto_save(&self, core: &mut CmdCore, mut parameter: Vec<String>) -> NSBoolResult {
         let Ok(mut time_interval) = (if parameter.is_empty() {
             CMDOPT
                 .input(t!("prompt.auto_save_interval").to_string())?
@@ -423,6 +514,7 @@ impl<'a> CommandParser<'a> {
                 .explain(&t!("err.fail_get_mutex_lock"))
                 .unwrap() = true;
             let mut printer = kit.m_rustyline_reader.create_external_printer().unwrap();
+            let mut scheduler = core.auto_save_scheduler(Duration::from_secs(time_interval))?;
 
             let handle = thread::spawn(move || {
                 loop {
@@ -431,7 +523,7 @@ impl<'a> CommandParser<'a> {
                         .explain(&t!("err.fail_get_mutex_lock"))
                         .unwrap();
                     let result = ref_condvar
-                        .wait_timeout(flag, Duration::from_secs(time_interval))
+                        .wait_timeout(flag, scheduler.poll_interval())
                         .explain(&t!("err.fail_get_mutex_lock"))
                         .unwrap();
                     if !*result.0 {
@@ -442,15 +534,44 @@ impl<'a> CommandParser<'a> {
                         .lock()
                         .explain(&t!("err.fail_get_mutex_lock"))
                         .unwrap();
+                    let (status_msg, need_save) =
+                        match scheduler.tick(|| core.is_game_running().unwrap_or(false)) {
+                            AutoSaveEvent::Idle => continue,
+                            AutoSaveEvent::GameStarted => (t!("msg.auto_save_resumed"), false),
+                            AutoSaveEvent::GameExited => (t!("msg.auto_save_paused"), false),
+                            AutoSaveEvent::FinalSave => (t!("msg.auto_save_final"), true),
+                            AutoSaveEvent::Save => (t!("msg.auto_saving"), true),
+                        };
                     printer
                         .print(
-                            format_with_pad_centered(&t!("msg.auto_saving"), 69)
+                            format_with_pad_centered(&status_msg, 69)
                                 .bright_yellow()
                                 .bold()
                                 .to_string(),
                         )
                         .unwrap();
-                    let (removed, latest) = core.auto_save(max_auto_saves).unwrap();
+                    if !need_save {
+                        continue;
+                    }
+                    let (removed, latest) = match core.auto_save(max_auto_saves) {
+                        Ok(Some(saved)) => saved,
+                        Ok(None) => continue,
+                        Err(err) => {
+                            printer
+                                .print(
+                                    format!(
+                                        "[WARNING] {}: {}",
+                                        t!("warn.auto_save_failed"),
+                                        err.get_explanation().join(", ")
+                                    )
+                                    .bright_yellow()
+                                    .bold()
+                                    .to_string(),
+                                )
+                                .unwrap();
+                            continue;
+                        }
+                    };
                     if let Some(removed_save) = removed {
                         printer
                             .print(
@@ -484,12 +605,32 @@ impl<'a> CommandParser<'a> {
     }
 
     fn load(&self, core: &mut CmdCore, mut parameter: Vec<String>) -> NSBoolResult {
+        let mode = match Self::option_value(&parameter, &["-m", "--mode"]) {
+            Some(mode) => match RestoreMode::parse(mode) {
+                Some(mode) => mode,
+                None => return throw(&t!("warn.invalid_restore_mode")),
+            },
+            None => RestoreMode::Full,
+        };
+        let dry_run = parameter
+            .iter()
+            .any(|item| item == "-n" || item == "--dry-run");
+        if let Some(position) = parameter
+            .iter()
+            .position(|item| item == "-m" || item == "--mode")
+        {
+            parameter.drain(position..(position + 2).min(parameter.len()));
+        }
+        parameter.retain(|item| item != "-n" && item != "--dry-run");
+
         if parameter.is_empty() {
             parameter.push(CMDOPT.input(t!("prompt.load_index").to_string())?);
         }
         if let Ok(index) = parameter.first().unwrap().as_str().parse::<usize>() {
             if index <= core.get_save_infos().saves.len() {
-                if core.load_save(index - 1)? {
+                if dry_run {
+                    CMDOPT.log(format!("{}", core.restore_plan(index - 1, mode)?));
+                } else if core.load_save(index - 1, mode)? {
                     CMDOPT.succeed();
                 } else {
                     CMDOPT.cancel();
@@ -513,32 +654,107 @@ impl<'a> CommandParser<'a> {
         Ok(true)
     }
 
-    fn print_log(&self, core: &mut CmdCore, start: usize) {
+    fn print_log(&self, core: &mut CmdCore, start: usize, parameter: &[String]) -> NSComResult {
         if core.get_save_infos().saves.is_empty() {
             CMDOPT.log(t!("msg.no_save").to_string() + "\n");
-            return;
+            return Ok(());
+        }
+        let verbose = Self::is_verbose(parameter);
+        let mut matches: Option<Vec<usize>> = None;
+        if let Some(perk) = Self::option_value(parameter, &["-p", "--perk"]) {
+            matches = Some(core.find_saves_with_perk(perk));
+            if matches.as_ref().is_some_and(|matches| matches.is_empty()) {
+                CMDOPT.log(t!("msg.no_save_with_perk").to_string() + "\n");
+                return Ok(());
+            }
         }
+        if let Some(seed) = Self::seed_filter(parameter)? {
+            let of_run = core.find_saves_of_run(seed);
+            matches = Some(match matches {
+                Some(matches) => matches.into_iter().filter(|i| of_run.contains(i)).collect(),
+                None => of_run,
+            });
+            if matches.as_ref().is_some_and(|matches| matches.is_empty()) {
+                CMDOPT.log(t!("msg.no_save_of_run").to_string() + "\n");
+                return Ok(());
+            }
+        }
+        let shown = |index: &usize| {
+            *index >= start
+                && matches
+                    .as_ref()
+                    .is_none_or(|matches| matches.contains(index))
+        };
+
         CMDOPT.log(t!("msg.locked_save_in_green").to_string() + "\n");
-        core.get_save_infos().saves[start..]
+        let groups = if parameter
             .iter()
-            .enumerate()
-            .for_each(|(index, item)| {
-                let save_log = format!("[{}] {}\n", index + 1 + start, item);
+            .any(|item| item == "-r" || item == "--runs")
+        {
+            core.runs()
+        } else {
+            vec![(None, (0..core.get_save_infos().saves.len()).collect())]
+        };
+        let grouped = groups.len() > 1 || groups.first().is_some_and(|(seed, _)| seed.is_some());
+        for (seed, indexes) in groups {
+            let indexes: Vec<usize> = indexes.into_iter().filter(shown).collect();
+            if indexes.is_empty() {
+                continue;
+            }
+            if grouped {
+                let header = match seed {
+                    Some(seed) => t!("msg.run_header", seed = seed, count = indexes.len()),
+                    None => t!("msg.unknown_run_header", count = indexes.len()),
+                };
+                CMDOPT.log(format!("{}\n", header).bold().to_string());
+            }
+            for index in indexes {
+                let item = &core.get_save_infos().saves[index];
+                let save_log = if verbose {
+                    format!("[{}] {:#}\n", index + 1, item)
+                } else {
+                    format!("[{}] {}\n", index + 1, item)
+                };
                 if item.is_locked() {
                     CMDOPT.log_green(save_log);
                 } else {
                     CMDOPT.log(save_log);
                 }
-            });
+            }
+        }
+        Ok(())
+    }
+    fn is_verbose(parameter: &[String]) -> bool {
+        parameter
+            .iter()
+            .any(|item| item == "-v" || item == "--verbose")
+    }
+    // the value following any of the given option names, e.g. the perk id after "--perk"
+    fn option_value<'p>(parameter: &'p [String], names: &[&str]) -> Option<&'p str> {
+        parameter
+            .iter()
+            .position(|item| names.contains(&item.as_str()))
+            .and_then(|index| parameter.get(index + 1))
+            .map(String::as_str)
     }
-    fn log(&self, core: &mut CmdCore, _parameter: Vec<String>) -> NSBoolResult {
-        self.print_log(core, 0);
+    fn seed_filter(parameter: &[String]) -> NSResult<Option<u32>> {
+        match Self::option_value(parameter, &["-s", "--seed"]) {
+            Some(seed) => match seed.parse::<u32>() {
+                Ok(seed) => Ok(Some(seed)),
+                Err(_) => throw(&t!("warn.invalid_seed")),
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn log(&self, core: &mut CmdCore, parameter: Vec<String>) -> NSBoolResult {
+        self.print_log(core, 0, &parameter)?;
         Ok(true)
     }
 
-    fn short_log(&self, core: &mut CmdCore, _parameter: Vec<String>) -> NSBoolResult {
+    fn short_log(&self, core: &mut CmdCore, parameter: Vec<String>) -> NSBoolResult {
         let start = std::cmp::max(core.get_save_infos().saves.len() as isize - 6, 0) as usize;
-        self.print_log(core, start);
+        self.print_log(core, start, &parameter)?;
         Ok(true)
     }
 
@@ -602,7 +818,10 @@ impl<'a> CommandParser<'a> {
         if parameter.is_empty() {
             parameter.push(CMDOPT.input(t!("prompt.delete_index").to_string())?);
         }
-        let indexes = Self::get_indexes_by_parameter(parameter)?;
+        let indexes = match Self::seed_filter(&parameter)? {
+            Some(seed) => core.find_saves_of_run(seed),
+            None => Self::get_indexes_by_parameter(parameter)?,
+        };
         if indexes.is_empty() {
             CMDOPT.cancel();
             return Ok(true);
@@ -618,8 +837,21 @@ impl<'a> CommandParser<'a> {
         Ok(true)
     }
 
+    fn prune(&self, core: &mut CmdCore, parameter: Vec<String>) -> NSBoolResult {
+        let dry_run = match parameter.first().map(|item| item.as_str()) {
+            None => false,
+            Some("--dry-run" | "-n") => true,
+            Some(_) => return throw(&t!("warn.incorrect_cmd_format")),
+        };
+        let removed = core.prune(dry_run)?;
+        if !dry_run && !removed.is_empty() {
+            CMDOPT.succeed();
+        }
+        Ok(true)
+    }
+
     fn lock(&self, core: &mut CmdCore, parameter: Vec<String>) -> NSBoolResult {
-        let indexes = Self::get_indexes_by_parameter(if parameter.is_empty() {
+        let parameter: Vec<String> = if parameter.is_empty() {
             CMDOPT
                 .input(t!("prompt.lock_index").to_string())?
                 .split(' ')
@@ -627,7 +859,11 @@ impl<'a> CommandParser<'a> {
                 .collect()
         } else {
             parameter
-        })?;
+        };
+        let indexes = match Self::seed_filter(&parameter)? {
+            Some(seed) => core.find_saves_of_run(seed),
+            None => Self::get_indexes_by_parameter(parameter)?,
+        };
         if indexes.is_empty() {
             CMDOPT.cancel();
             return Ok(true);
@@ -637,7 +873,7 @@ impl<'a> CommandParser<'a> {
     }
 
     fn unlock(&self, core: &mut CmdCore, parameter: Vec<String>) -> NSBoolResult {
-        let indexes = Self::get_indexes_by_parameter(if parameter.is_empty() {
+        let parameter: Vec<String> = if parameter.is_empty() {
             CMDOPT
                 .input(t!("prompt.unlock_index").to_string())?
                 .split(' ')
@@ -645,7 +881,11 @@ impl<'a> CommandParser<'a> {
                 .collect()
         } else {
             parameter
-        })?;
+        };
+        let indexes = match Self::seed_filter(&parameter)? {
+            Some(seed) => core.find_saves_of_run(seed),
+            None => Self::get_indexes_by_parameter(parameter)?,
+        };
         if indexes.is_empty() {
             CMDOPT.cancel();
             return Ok(true);
@@ -663,4 +903,255 @@ impl<'a> CommandParser<'a> {
         }
         Ok(true)
     }
+
+    fn inspect(&self, core: &mut CmdCore, mut parameter: Vec<String>) -> NSBoolResult {
+        if parameter.is_empty() {
+            parameter.push(CMDOPT.input(t!("prompt.inspect_index").to_string())?);
+        }
+        let Ok(index) = parameter.first().unwrap().parse::<usize>() else {
+            CMDOPT.cancel();
+            return Ok(true);
+        };
+        let Some(item) = index
+            .checked_sub(1)
+            .and_then(|index| core.get_save_infos().saves.get(index))
+        else {
+            return throw(&t!("warn.invalid_index"));
+        };
+        CMDOPT.log_green(format!("[{}] {:#}\n", index, item));
+
+        let wands = core.inspect_wands(index - 1)?;
+        if wands.is_empty() {
+            CMDOPT.log(t!("msg.no_wand").to_string() + "\n");
+        }
+        for wand in wands {
+            CMDOPT.log(format!("{}\n", wand));
+        }
+        Ok(true)
+    }
+
+    fn stats(&self, core: &mut CmdCore, parameter: Vec<String>) -> NSBoolResult {
+        let report = core.stats()?;
+        if parameter.iter().any(|item| item == "--json") {
+            CMDOPT.log(report.to_json()? + "\n");
+        } else if report.m_sessions.is_empty() {
+            CMDOPT.log(t!("msg.no_stats").to_string() + "\n");
+        } else {
+            CMDOPT.log(format!("{}\n", report.m_summary));
+        }
+        Ok(true)
+    }
+
+    fn diff(&self, core: &mut CmdCore, mut parameter: Vec<String>) -> NSBoolResult {
+        let verbose = Self::is_verbose(&parameter);
+        parameter.retain(|item| item != "-v" && item != "--verbose");
+        if parameter.is_empty() {
+            parameter = CMDOPT
+                .input(t!("prompt.diff_index").to_string())?
+                .split(' ')
+                .filter(|item| !item.is_empty())
+                .map(|item| item.to_string())
+                .collect();
+        }
+        if parameter.is_empty() {
+            CMDOPT.cancel();
+            return Ok(true);
+        }
+        // "live" or a missing second index stands for the live save
+        let to_index = |item: Option<&String>| -> NSResult<Option<usize>> {
+            match item.map(|item| item.as_str()) {
+                None | Some("live") => Ok(None),
+                Some(item) => match item.parse::<usize>() {
+                    Ok(index) if index > 0 => Ok(Some(index - 1)),
+                    _ => throw(&t!("warn.invalid_index")),
+                },
+            }
+        };
+        let diff = core.diff(to_index(parameter.first())?, to_index(parameter.get(1))?)?;
+        if verbose {
+            CMDOPT.log(format!("{:#}", diff));
+        } else {
+            CMDOPT.log(format!("{}", diff));
+        }
+        Ok(true)
+    }
+
+    fn config(&self, core: &mut CmdCore, mut parameter: Vec<String>) -> NSBoolResult {
+        if parameter.len() >= 2 {
+            let key = parameter.remove(0);
+            core.set_setting(&key, &parameter.join(" "))?;
+            CMDOPT.succeed();
+            return Ok(true);
+        }
+
+        let entries = core.get_settings().entries()?;
+        if let Some(key) = parameter.first()
+            && !entries.iter().any(|(name, _)| name == key)
+        {
+            return throw(&t!("warn.unknown_setting", key = key));
+        }
+        for (name, value) in entries {
+            if parameter.first().is_none_or(|key| *key == name) {
+                print_with_pad(&name, 40);
+                CMDOPT.log_green(value + "\n");
+            }
+        }
+        Ok(true)
+    }
+
+    fn mod_profile(&self, core: &mut CmdCore, mut parameter: Vec<String>) -> NSBoolResult {
+        let action = if parameter.is_empty() {
+            "list".to_string()
+        } else {
+            parameter.remove(0)
+        };
+        if action == "list" || action == "ls" {
+            let profiles = core.get_mod_profiles();
+            if profiles.is_empty() {
+                CMDOPT.log(t!("msg.no_mod_profile").to_string() + "\n");
+            }
+            for (index, profile) in profiles.iter().enumerate() {
+                CMDOPT.log(format!("[{}] {}\n", index + 1, profile));
+            }
+            return Ok(true);
+        }
+
+        let name = if parameter.is_empty() {
+            CMDOPT.input(t!("prompt.mod_profile_name").to_string())?
+        } else {
+            parameter.join(" ")
+        };
+        if name.is_empty() {
+            CMDOPT.cancel();
+            return Ok(true);
+        }
+        let done = match action.as_str() {
+            "save" | "s" => core.capture_mod_profile(name)?,
+            "apply" | "a" => {
+                core.apply_mod_profile(&name)?;
+                true
+            }
+            "delete" | "d" => core.delete_mod_profile(&name)?,
+            _ => return throw(&t!("warn.incorrect_cmd_format")),
+        };
+        if done {
+            CMDOPT.succeed();
+        } else {
+            CMDOPT.cancel();
+        }
+        Ok(true)
+    }
+
+    fn progress_profile(&self, core: &mut CmdCore, mut parameter: Vec<String>) -> NSBoolResult {
+        let verbose = Self::is_verbose(&parameter);
+        parameter.retain(|item| item != "-v" && item != "--verbose");
+        let action = if parameter.is_empty() {
+            "list".to_string()
+        } else {
+            parameter.remove(0)
+        };
+        match action.as_str() {
+            "list" | "ls" => {
+                let profiles = core.get_progress_profiles();
+                if profiles.is_empty() {
+                    CMDOPT.log(t!("msg.no_progress_profile").to_string() + "\n");
+                }
+                for (index, profile) in profiles.iter().enumerate() {
+                    CMDOPT.log(format!("[{}] {}\n", index + 1, profile));
+                }
+                return Ok(true);
+            }
+            // "live" or a missing second name stands for the live progress
+            "compare" | "c" => {
+                let Some(old) = parameter.first() else {
+                    return throw(&t!("warn.incorrect_cmd_format"));
+                };
+                let name_of = |item: &str| (item != "live").then(|| item.to_string());
+                let diff = core.compare_progress(
+                    name_of(old).as_deref(),
+                    parameter.get(1).and_then(|item| name_of(item)).as_deref(),
+                )?;
+                if verbose {
+                    CMDOPT.log(format!("{:#}", diff));
+                } else {
+                    CMDOPT.log(format!("{}", diff));
+                }
+                return Ok(true);
+            }
+            _ => (),
+        }
+
+        let name = if parameter.is_empty() {
+            CMDOPT.input(t!("prompt.progress_profile_name").to_string())?
+        } else {
+            parameter.join(" ")
+        };
+        if name.is_empty() {
+            CMDOPT.cancel();
+            return Ok(true);
+        }
+        let done = match action.as_str() {
+            "save" | "s" => core.capture_progress_profile(name)?,
+            "switch" | "sw" => core.switch_progress_profile(&name)?,
+            "delete" | "d" => core.delete_progress_profile(&name)?,
+            _ => return throw(&t!("warn.incorrect_cmd_format")),
+        };
+        if done {
+            CMDOPT.succeed();
+        } else {
+            CMDOPT.cancel();
+        }
+        Ok(true)
+    }
+
+    fn paths(&self, core: &mut CmdCore, _parameter: Vec<String>) -> NSBoolResult {
+        for decision in core.path_diagnostics() {
+            if decision.m_path.is_some() {
+                CMDOPT.log(format!("{}\n", decision));
+            } else {
+                CMDOPT.warning(format!("{}\n", decision));
+            }
+        }
+        Ok(true)
+    }
+
+    fn shared_settings(&self, core: &mut CmdCore, mut parameter: Vec<String>) -> NSBoolResult {
+        let action = if parameter.is_empty() {
+            "list".to_string()
+        } else {
+            parameter.remove(0)
+        };
+        match action.as_str() {
+            "list" | "ls" => {
+                let indexes = core.saves_with_shared_settings();
+                if indexes.is_empty() {
+                    CMDOPT.log(t!("msg.no_save_with_shared_settings").to_string() + "\n");
+                }
+                for index in indexes {
+                    let item = &core.get_save_infos().saves[index];
+                    CMDOPT.log(format!("[{}] {}\n", index + 1, item));
+                }
+                Ok(true)
+            }
+            "restore" | "r" => {
+                if parameter.is_empty() {
+                    parameter.push(CMDOPT.input(t!("prompt.shared_settings_index").to_string())?);
+                }
+                let Ok(index) = parameter.first().unwrap().parse::<usize>() else {
+                    CMDOPT.cancel();
+                    return Ok(true);
+                };
+                if index == 0 {
+                    return throw(&t!("warn.invalid_index"));
+                }
+                if core.restore_shared_settings(index - 1)? {
+                    CMDOPT.succeed();
+                } else {
+                    CMDOPT.cancel();
+                }
+                Ok(true)
+            }
+            _ => throw(&t!("warn.incorrect_cmd_format")),
+        }
+    }
 }
@@ -1,10 +1,40 @@
 mod utils;
 
+pub use utils::auto_save_scheduler::{AutoSaveEvent, AutoSaveScheduler};
+pub use utils::diff::{GameStateDiff, SaveDiff, WandChange};
 pub use utils::error::*;
 use utils::file_operator::FileOperator;
+use utils::game_build::read_game_build;
+use utils::game_state::minimap::render_minimap;
+pub use utils::game_state::mods::{ModList, ModMismatch};
+pub use utils::game_state::perks::{GameEffect, PerkSummary, PickedPerk};
+pub use utils::game_state::player::{Biome, PlayerStats};
+pub use utils::game_state::progress::{ProgressDiff, ProgressSummary};
+use utils::game_state::stats::read_world_seed;
+pub use utils::game_state::stats::{SessionStats, StatsReport, StatsSummary};
+pub use utils::game_state::wands::WandSummary;
+pub use utils::game_state::world::WorldProgress;
+#[cfg(target_os = "linux")]
+use utils::launcher::Launcher;
+pub use utils::launcher::{LaunchMode, LauncherKind};
+pub use utils::live_status::LiveStatus;
+pub use utils::mod_profile::ModProfile;
 pub use utils::output_manager;
 use utils::output_manager::OutputManager;
-pub use utils::save_infos::{AllInfos, SingleSave};
+pub use utils::path_decision::PathDecision;
+use utils::process;
+pub use utils::progress_profile::ProgressProfile;
+pub use utils::restore_mode::{RestoreMode, RestorePlan};
+use utils::retention::RetentionCandidate;
+pub use utils::retention::RetentionPolicy;
+pub use utils::save_infos::{AllInfos, SaveKind, SingleSave};
+pub use utils::settings::Settings;
+use utils::snapshot_filter::PathFilter;
+pub use utils::snapshot_filter::SnapshotFilter;
+#[cfg(target_os = "linux")]
+use utils::steam::SteamLayout;
+#[cfg(target_os = "linux")]
+use utils::wine::WineLauncher;
 
 // third-party imports
 use chrono::{Datelike, Local, Timelike};
@@ -17,34 +47,29 @@ i18n!("locales");
 // std imports
 #[cfg(target_os = "linux")]
 use directories::BaseDirs;
-#[cfg(target_os = "linux")]
-use std::fs::File;
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
-#[cfg(target_os = "linux")]
 use std::path::PathBuf;
+#[cfg(target_os = "windows")]
 use std::process::Command;
+use std::time::Duration;
 
-#[cfg(target_os = "linux")]
-const APPID: &str = "881100";
+// opening it makes the Steam client start Noita
+#[cfg(target_os = "windows")]
+const STEAM_LAUNCH_URL: &str = "steam://rungameid/881100";
 
 #[derive(Debug)]
 pub struct Core<Opm: OutputManager> {
     #[cfg(target_os = "linux")]
-    m_steam_dir: PathBuf,
-    #[cfg(target_os = "linux")]
-    m_steam_runtime_runsh: PathBuf,
-    #[cfg(target_os = "linux")]
-    m_proton_exe: PathBuf,
-    #[cfg(target_os = "linux")]
-    m_noita_exe: PathBuf,
-    #[cfg(target_os = "linux")]
-    m_noita_dir: PathBuf,
+    m_launcher: Box<dyn Launcher>,
 
     m_file_operator: FileOperator,
     m_info: AllInfos,
     m_opm: Opm,
     m_locale: &'static str,
+    // raised while constructing, a frontend may not be able to show messages before `new`
+    // returns, see `report_startup_warnings`
+    m_startup_warnings: Vec<String>,
 }
 
 impl<Opm: OutputManager> Core<Opm> {
@@ -64,31 +89,58 @@ impl<Opm: OutputManager> Core<Opm> {
             None => "en-US",
         };
         rust_i18n::set_locale(locale);
-        let file_operator = FileOperator::new()?;
-
+        // the live save is wherever the launcher runs Noita
         #[cfg(target_os = "linux")]
-        let dir = BaseDirs::new().unwrap();
+        let (launcher, chosen, startup_warnings) = {
+            let settings = FileOperator::read_settings()?;
+            match Self::make_launcher(&opm, &settings) {
+                Ok((launcher, chosen)) => (launcher, chosen, Vec::new()),
+                // e.g. a hand edited "infos.json", Steam keeps the manager usable until it's fixed
+                Err(e) => {
+                    let warning = t!("launcher_unusable_using_steam").to_string()
+                        + &format!(": {}\n", e.get_explanation().join(", "));
+                    let (steam, chosen) = Self::choose_steam(&opm, &settings);
+                    (Box::new(steam) as Box<dyn Launcher>, chosen, vec![warning])
+                }
+            }
+        };
+        #[cfg(target_os = "windows")]
+        let startup_warnings = Vec::new();
+        #[cfg(target_os = "linux")]
+        let mut file_operator = FileOperator::new(launcher.live_save_path())?;
+        #[cfg(target_os = "windows")]
+        let mut file_operator = FileOperator::new(FileOperator::get_noita_save_path()?)?;
+        let mut infos = file_operator.load_infos()?;
+        if infos.migrate() {
+            file_operator
+                .write_infos(&infos)
+                .explain_fatal(&t!("fail_migrate_info_file"))?;
+        }
+        // remembered so that the question isn't asked on every start
         #[cfg(target_os = "linux")]
-        let local_share_dir = dir.data_dir();
+        if let Some(chosen) = chosen {
+            infos.get_settings_mut().steam_root = chosen;
+            file_operator.write_infos(&infos)?;
+        }
+
         Ok(Self {
             #[cfg(target_os = "linux")]
-            m_steam_dir: local_share_dir.join("Steam"),
-            #[cfg(target_os = "linux")]
-            m_steam_runtime_runsh: local_share_dir.join("Steam/ubuntu12_32/steam-runtime/run.sh"),
-            #[cfg(target_os = "linux")]
-            m_proton_exe: local_share_dir
-                .join("Steam/steamapps/common/Proton - Experimental/proton"),
-            #[cfg(target_os = "linux")]
-            m_noita_exe: local_share_dir.join("Steam/steamapps/common/Noita/noita.exe"),
-            #[cfg(target_os = "linux")]
-            m_noita_dir: local_share_dir.join("Steam/steamapps/common/Noita"),
-            m_info: file_operator.load_infos()?,
+            m_launcher: launcher,
+            m_info: infos,
             m_file_operator: file_operator,
             m_opm: opm,
             m_locale: locale,
+            m_startup_warnings: startup_warnings,
         })
     }
 
+    // shows the warnings raised by `new`, only the first call has anything to show
+    pub fn report_startup_warnings(&mut self) {
+        for warning in self.m_startup_warnings.drain(..) {
+            self.m_opm.warning(warning);
+        }
+    }
+
     #[inline]
     fn get_date() -> String {
         Local::now().format(&t!("date_format")).to_string()
@@ -114,9 +166,171 @@ impl<Opm: OutputManager> Core<Opm> {
         self.m_locale
     }
 
-    pub fn startgame(&self) -> NSComResult {
+    #[inline]
+    pub fn get_settings(&self) -> &Settings {
+        self.m_info.get_settings()
+    }
+
+    pub fn set_setting(&mut self, key: &str, value: &str) -> NSComResult {
+        let mut settings = self.m_info.get_settings().clone();
+        settings.set(key, value)?;
+        // a launcher that can't be made from the new settings leaves them unchanged
+        #[cfg(target_os = "linux")]
+        if Self::launcher_changed(self.m_info.get_settings(), &settings) {
+            let (launcher, chosen) = Self::make_launcher(&self.m_opm, &settings)?;
+            if let Some(chosen) = chosen {
+                settings.steam_root = chosen;
+            }
+            self.m_file_operator
+                .set_noita_save_path(launcher.live_save_path());
+            self.m_launcher = launcher;
+        }
+        *self.m_info.get_settings_mut() = settings;
+        self.write_infos()
+    }
+
+    // only a change to the settings the active launcher is made from makes it again, so
+    // unrelated edits never probe for Steam installs or ask for one
+    #[cfg(target_os = "linux")]
+    fn launcher_changed(old: &Settings, new: &Settings) -> bool {
+        old.launcher != new.launcher
+            || match new.launcher {
+                LauncherKind::Steam => {
+                    old.steam_root != new.steam_root || old.proton_path != new.proton_path
+                }
+                LauncherKind::Wine => {
+                    old.wine_prefix != new.wine_prefix
+                        || old.wine_exe != new.wine_exe
+                        || old.wine_binary != new.wine_binary
+                }
+            }
+    }
+
+    // the launcher picked by the "launcher" setting, with the Steam root to remember if the
+    // user has just been asked for one
+    #[cfg(target_os = "linux")]
+    fn make_launcher(
+        opm: &Opm,
+        settings: &Settings,
+    ) -> NSResult<(Box<dyn Launcher>, Option<String>)> {
+        Ok(match settings.launcher {
+            LauncherKind::Steam => {
+                let (steam, chosen) = Self::choose_steam(opm, settings);
+                (Box::new(steam), chosen)
+            }
+            LauncherKind::Wine => (Box::new(WineLauncher::new(settings)?), None),
+        })
+    }
+
+    // the Steam install holding Noita, "steam_root" wins if it's set, several installs holding
+    // it are offered one by one, the answer is returned to be remembered in "steam_root"
+    #[cfg(target_os = "linux")]
+    fn choose_steam(opm: &Opm, settings: &Settings) -> (SteamLayout, Option<String>) {
+        let proton = &settings.proton_path;
+        if !settings.steam_root.is_empty() {
+            return (
+                SteamLayout::detect(
+                    PathBuf::from(&settings.steam_root),
+                    t!("steam_root_from_setting").to_string(),
+                    proton,
+                ),
+                None,
+            );
+        }
+        let mut installs = SteamLayout::detect_installs(proton);
+        let holding: Vec<usize> = (0..installs.len())
+            .filter(|&index| installs[index].0.has_noita())
+            .collect();
+        match holding[..] {
+            [] if installs.is_empty() => (
+                SteamLayout::detect(
+                    BaseDirs::new().unwrap().data_dir().join("Steam"),
+                    t!("steam_root_default").to_string(),
+                    proton,
+                ),
+                None,
+            ),
+            [] => {
+                let (steam, kind) = installs.swap_remove(0);
+                let reason = t!("steam_root_without_noita", kind = kind).to_string();
+                (steam.with_root_reason(reason), None)
+            }
+            [only] => {
+                let (steam, kind) = installs.swap_remove(only);
+                let reason = t!("steam_root_only_with_noita", kind = kind).to_string();
+                (steam.with_root_reason(reason), None)
+            }
+            _ => {
+                // only a frontend that really asks gets its answer saved as `steam_root`
+                let candidates = if opm.can_confirm() { &holding[..] } else { &[] };
+                for &index in candidates {
+                    let (steam, kind) = &installs[index];
+                    let question = t!(
+                        "choose_steam_root",
+                        count = holding.len(),
+                        kind = kind,
+                        path = steam.steam_dir().display()
+                    );
+                    // a failing prompt is treated like a "no", the first install is used then
+                    if opm.confirm(question.to_string()).unwrap_or(false) {
+                        let (steam, kind) = installs.swap_remove(index);
+                        let chosen = steam.steam_dir().to_string_lossy().to_string();
+                        let reason = t!("steam_root_chosen", kind = kind).to_string();
+                        return (steam.with_root_reason(reason), Some(chosen));
+                    }
+                }
+                let (steam, kind) = installs.swap_remove(holding[0]);
+                let reason = t!("steam_root_first_with_noita", kind = kind).to_string();
+                (steam.with_root_reason(reason), None)
+            }
+        }
+    }
+
+    // every path the manager relies on and why it was chosen
+    pub fn path_diagnostics(&self) -> Vec<PathDecision> {
         #[cfg(target_os = "windows")]
         {
+            let noipath = self.m_info.get_exe_path();
+            vec![
+                if noipath.exists() && noipath.ends_with("noita.exe") {
+                    PathDecision::new(
+                        t!("path_item_noita"),
+                        Some(noipath.to_path_buf()),
+                        t!("noita_path_from_setpath"),
+                    )
+                } else {
+                    PathDecision::new(t!("path_item_noita"), None, t!("please_set_noita_path"))
+                },
+                PathDecision::new(
+                    t!("path_item_live_save"),
+                    Some(self.m_file_operator.get_live_save_path().to_path_buf()),
+                    t!("live_save_in_local_low"),
+                ),
+            ]
+        }
+        #[cfg(target_os = "linux")]
+        self.m_launcher.path_decisions()
+    }
+
+    #[inline]
+    pub fn is_game_running(&self) -> NSBoolResult {
+        process::is_noita_running()
+    }
+
+    // `mode` left out falls back to the "launch_mode" setting
+    pub fn startgame(&self, mode: Option<LaunchMode>) -> NSComResult {
+        let mode = mode.unwrap_or(self.m_info.get_settings().launch_mode);
+        #[cfg(target_os = "windows")]
+        {
+            if mode == LaunchMode::Steam {
+                // the client registers the "steam://" protocol, "start" hands it over
+                Command::new("cmd")
+                    .args(["/C", "start", "", STEAM_LAUNCH_URL])
+                    .creation_flags(0x08000000) // no console window
+                    .spawn()
+                    .explain(&t!("fail_start_steam_client", program = STEAM_LAUNCH_URL))?;
+                return Ok(());
+            }
             let noipath = self.m_info.get_exe_path();
             if !(noipath.exists() && noipath.ends_with("noita.exe")) {
                 return throw(&t!("please_set_noita_path"));
@@ -132,53 +346,96 @@ impl<Opm: OutputManager> Core<Opm> {
         }
         #[cfg(target_os = "linux")]
         {
-            self.m_opm
-                .warning(t!("start_without_steam_warning").to_string() + "\n");
-
-            let compat_data_path = self
-                .m_steam_dir
-                .join(format!("steamapps/compatdata/{APPID}"));
-            let shader_cache_path = self
-                .m_steam_dir
-                .join(format!("steamapps/shadercache/{APPID}"));
-            let proton_tool_path = self
-                .m_proton_exe
-                .parent()
-                .unwrap_or(self.m_steam_dir.as_path());
-
-            let mut cmd = if self.m_steam_runtime_runsh.exists() {
-                let mut c = Command::new(&self.m_steam_runtime_runsh);
-                c.arg(&self.m_proton_exe);
-                c
-            } else {
-                Command::new(&self.m_proton_exe)
-            };
-
-            cmd.arg("waitforexitandrun")
-                .arg(&self.m_noita_exe)
-                .current_dir(&self.m_noita_dir)
-                .env("STEAM_COMPAT_CLIENT_INSTALL_PATH", &self.m_steam_dir)
-                .env("STEAM_COMPAT_DATA_PATH", &compat_data_path)
-                .env("STEAM_COMPAT_SHADER_PATH", &shader_cache_path)
-                .env("STEAM_COMPAT_TOOL_PATH", proton_tool_path)
-                .env("STEAM_COMPAT_TOOL_PATHS", proton_tool_path)
-                .env("STEAM_COMPAT_APP_ID", APPID)
-                .env("SteamAppId", APPID)
-                .env("SteamGameId", APPID)
-                .env("WINEPREFIX", compat_data_path.join("pfx"))
-                .stdout(File::create("/dev/null")?)
-                .stderr(File::create("/dev/null")?)
-                .spawn()?;
+            if mode == LaunchMode::Steam {
+                return self.m_launcher.launch_with_client();
+            }
+            if self.m_launcher.kind() == LauncherKind::Steam {
+                self.m_opm
+                    .warning(t!("start_without_steam_warning").to_string() + "\n");
+            }
+            self.m_launcher.launch()?;
         }
         Ok(())
     }
 
+    // the directory holding "noita.exe", None on Windows until a valid path has been set
+    fn noita_dir(&self) -> Option<PathBuf> {
+        #[cfg(target_os = "windows")]
+        {
+            let noipath = self.m_info.get_exe_path();
+            if noipath.ends_with("noita.exe") {
+                noipath.parent().map(|dir| dir.to_path_buf())
+            } else {
+                None
+            }
+        }
+        #[cfg(target_os = "linux")]
+        Some(self.m_launcher.noita_dir())
+    }
+
+    #[inline]
+    fn current_game_build(&self) -> NSResult<Option<String>> {
+        match self.noita_dir() {
+            Some(dir) => read_game_build(&dir),
+            None => Ok(None),
+        }
+    }
+
+    // None if both builds are the same or either of them is unknown
+    fn game_build_mismatch(&self, save: &SingleSave) -> Option<String> {
+        let saved = save.get_game_build()?;
+        let current = self.current_game_build().unwrap_or_else(|e| {
+            self.m_opm.warning(
+                t!("fail_read_current_game_build").to_string()
+                    + &format!(": {}\n", e.get_explanation().join(", ")),
+            );
+            None
+        })?;
+        (saved != current).then(|| {
+            t!(
+                "game_build_mismatch",
+                save_name = save.get_name(),
+                saved = saved,
+                current = current
+            )
+            .to_string()
+        })
+    }
+
+    // None if the same mods are enabled in the same order, or either side is unknown
+    fn mod_mismatch(&self, save: &SingleSave) -> Option<String> {
+        let saved = save.get_mods()?;
+        let current = ModList::from_save(self.m_file_operator.get_live_save_path())
+            .unwrap_or_else(|e| {
+                self.m_opm.warning(
+                    t!("fail_read_current_mods").to_string()
+                        + &format!(": {}\n", e.get_explanation().join(", ")),
+                );
+                None
+            })?;
+        saved.mismatch(&current).map(|mismatch| {
+            t!("mod_mismatch", save_name = save.get_name()).to_string() + &mismatch.to_string()
+        })
+    }
+
     pub fn set_noita_path(&mut self, path: String) -> NSComResult {
         self.m_info.set_noita_path(path);
         self.write_infos()
     }
 
-    pub fn save(&mut self, save_name: String, save_note: String) -> NSComResult {
+    #[inline]
+    // `with_shared` snapshots "save_shared" too, regardless of `snapshot_shared_settings`
+    pub fn save(&mut self, save_name: String, save_note: String, with_shared: bool) -> NSComResult {
+        self.save_with_kind(save_name, save_note, SaveKind::Manual, with_shared)
+    }
+
+    fn save_with_kind(
+        &mut self,
+        save_name: String,
+        save_note: String,
+        kind: SaveKind,
+        with_shared: bool,
+    ) -> NSComResult {
         if self
             .m_info
             .saves
@@ -192,14 +449,136 @@ impl<Opm: OutputManager> Core<Opm> {
             return throw(&t!("save_name_empty"));
         }
 
-        self.m_file_operator.save(&save_name)?;
-        self.m_info.saves.push(SingleSave::new(
+        let filter = self.m_info.get_settings().snapshot_filter.clone();
+        self.m_file_operator.save(
+            &save_name,
+            self.m_info.get_settings().compare_hashes,
+            &filter.compile()?,
+        )?;
+        let has_shared = (with_shared || self.m_info.get_settings().snapshot_shared_settings)
+            && self.m_file_operator.save_shared(&save_name)?;
+        let mut save = SingleSave::new(
             Self::get_date(),
             Self::get_time(),
+            Local::now().timestamp(),
             save_name,
             save_note,
-        ));
+            kind,
+        );
+        save.modify_has_shared_settings(has_shared);
+        save.modify_filter((!filter.is_empty()).then_some(filter));
+        self.read_snapshot_details(&mut save);
+        self.m_info.saves.push(save);
         self.write_infos()?;
+        self.enforce_storage_budget()?;
+        Ok(())
+    }
+
+    // the rules `save` was taken with, loading and comparing it must honour the same ones
+    fn filter_of(save: &SingleSave) -> NSResult<PathFilter> {
+        save.get_filter()
+            .map_or(Ok(PathFilter::default()), SnapshotFilter::compile)
+    }
+
+    // game details are only informative, a snapshot that can't be parsed is still a valid save
+    fn read_snapshot_details(&self, save: &mut SingleSave) {
+        let save_path = self.m_file_operator.get_save_path(save.get_name());
+        let warn = |e: NSError| {
+            self.m_opm.warning(
+                t!("fail_read_snapshot_details", save_name = save.get_name()).to_string()
+                    + &format!(": {}\n", e.get_explanation().join(", ")),
+            );
+        };
+        let player = PlayerStats::from_save(&save_path).unwrap_or_else(|e| {
+            warn(e);
+            None
+        });
+        let perks = PerkSummary::from_save(&save_path).unwrap_or_else(|e| {
+            warn(e);
+            None
+        });
+        let world = WorldProgress::from_save(&save_path).unwrap_or_else(|e| {
+            warn(e);
+            None
+        });
+        // read from the live save, the snapshot's filter may have left out "stats" or "world"
+        let seed = read_world_seed(self.m_file_operator.get_live_save_path()).unwrap_or_else(|e| {
+            warn(e);
+            None
+        });
+        let mods = ModList::from_save(&save_path).unwrap_or_else(|e| {
+            warn(e);
+            None
+        });
+        // read from the install rather than the snapshot, the game is what the snapshot depends on
+        let game_build = self.current_game_build().unwrap_or_else(|e| {
+            warn(e);
+            None
+        });
+        save.modify_player(player);
+        save.modify_perks(perks);
+        save.modify_world(world);
+        save.modify_seed(seed);
+        save.modify_game_build(game_build);
+        save.modify_mods(mods);
+    }
+
+    // evicts the oldest unlocked auto and quick saves until the library fits the storage budget,
+    // the newest save is never evicted
+    fn enforce_storage_budget(&mut self) -> NSComResult {
+        let budget_mb = self.m_info.get_settings().storage_budget_gb * 1024.0;
+        if budget_mb <= 0.0 {
+            return Ok(());
+        }
+        // only the snapshots count, the backup and profile folders next to them are never evicted
+        let mut usage = 0.0;
+        for item in self.m_info.saves.iter() {
+            usage += self.m_file_operator.get_save_usage(item.get_name())?;
+        }
+        if usage <= budget_mb {
+            return Ok(());
+        }
+
+        let mut any_evicted = false;
+        for name in self.m_info.storage_eviction_order() {
+            if usage <= budget_mb {
+                break;
+            }
+            usage -= self.m_file_operator.get_save_usage(&name)?;
+            self.m_file_operator
+                .remove_save(&name)
+                .explain(&t!("delete_save_fail"))?;
+            self.m_info.saves.retain(|item| item.get_name() != name);
+            any_evicted = true;
+            self.m_opm
+                .log(t!("storage_budget_evicted", save_name = name).to_string() + "\n");
+        }
+        if any_evicted {
+            self.m_file_operator
+                .write_infos(&self.m_info)
+                .explain(&t!("fail_modify_info_after_delete"))?;
+        }
+        if usage <= budget_mb {
+            return Ok(());
+        }
+        // what's left over may just be the newest auto or quick save, which is never evicted
+        let mut kept_mb = 0.0;
+        for item in self.m_info.saves.iter() {
+            if item.is_locked() || !matches!(item.get_kind(), SaveKind::Auto | SaveKind::Quick) {
+                kept_mb += self.m_file_operator.get_save_usage(item.get_name())?;
+            }
+        }
+        if kept_mb > budget_mb {
+            self.m_opm.warning(
+                t!(
+                    "storage_budget_exceeded_by_kept",
+                    usage = format!("{:.2}", kept_mb / 1024.0),
+                    budget = format!("{:.2}", budget_mb / 1024.0)
+                )
+                .to_string()
+                    + "\n",
+            );
+        }
         Ok(())
     }
 
@@ -230,12 +609,20 @@ impl<Opm: OutputManager> Core<Opm> {
         hash(now.minute(), &mut name);
         hash(now.second(), &mut name);
 
-        self.save(name, "".to_string())?;
+        let kind = if is_auto_save {
+            SaveKind::Auto
+        } else {
+            SaveKind::Quick
+        };
+        self.save_with_kind(name, "".to_string(), kind, false)?;
         Ok(())
     }
 
     pub fn overwrite_save(&mut self) -> NSBoolResult {
-        if let Some(save) = self.m_info.saves.last_mut() {
+        let with_hashes = self.m_info.get_settings().compare_hashes;
+        // the new content follows the current rules, not those of the old snapshot
+        let filter = self.m_info.get_settings().snapshot_filter.clone();
+        if let Some(mut save) = self.m_info.saves.last().cloned() {
             save.protect()?;
             let name = save.get_name();
 
@@ -247,12 +634,24 @@ impl<Opm: OutputManager> Core<Opm> {
             }
 
             self.m_file_operator.remove_save(name)?;
-            self.m_file_operator.save(name)?;
+            self.m_file_operator
+                .save(name, with_hashes, &filter.compile()?)?;
+            let has_shared = (save.has_shared_settings()
+                || self.m_info.get_settings().snapshot_shared_settings)
+                && self.m_file_operator.save_shared(name)?;
+            save.modify_has_shared_settings(has_shared);
+            save.modify_filter((!filter.is_empty()).then_some(filter));
 
             save.modify_date(Self::get_date());
             save.modify_time(Self::get_time());
+            save.modify_timestamp(Local::now().timestamp());
+            self.read_snapshot_details(&mut save);
+            if let Some(latest) = self.m_info.saves.last_mut() {
+                *latest = save;
+            }
 
             self.write_infos()?;
+            self.enforce_storage_budget()?;
             Ok(true)
         } else {
             throw(&t!(
@@ -262,17 +661,40 @@ impl<Opm: OutputManager> Core<Opm> {
         }
     }
 
+    pub fn auto_save_scheduler(&self, interval: Duration) -> NSResult<AutoSaveScheduler> {
+        let settings = self.m_info.get_settings();
+        let game_running = settings.auto_save_only_in_game && self.is_game_running()?;
+        Ok(AutoSaveScheduler::new(
+            interval,
+            settings.auto_save_only_in_game,
+            Duration::from_secs(settings.final_save_delay_secs),
+            game_running,
+        ))
+    }
+
+    // returns None if the live save hasn't changed since the latest snapshot
     pub fn auto_save(
         &mut self,
         max_auto_saves: usize,
-    ) -> NSResult<(Option<SingleSave>, SingleSave)> {
+    ) -> NSResult<Option<(Option<SingleSave>, SingleSave)>> {
+        if let Some(latest) = self.m_info.saves.last()
+            && self
+                .m_file_operator
+                .live_matches_snapshot(latest.get_name(), &Self::filter_of(latest)?)?
+        {
+            self.m_opm.log(
+                t!("auto_save_skipped_unchanged", save_name = latest.get_name()).to_string() + "\n",
+            );
+            return Ok(None);
+        }
+
         let saves = &mut self.m_info.saves;
         let mut removed_save = None;
         let auto_saves: Vec<(usize, String)> = saves
             .iter()
             .enumerate()
             .filter_map(|(index, item)| {
-                if item.get_name().starts_with("as_") {
+                if item.get_kind() == SaveKind::Auto && !item.is_locked() {
                     Some((index, item.get_name().to_string()))
                 } else {
                     None
@@ -291,18 +713,64 @@ impl<Opm: OutputManager> Core<Opm> {
             .write_infos(&self.m_info)
             .explain(&t!("fail_modify_info_after_delete"))?;
         let latest = self.m_info.saves.last().unwrap();
-        Ok((removed_save, (latest.clone())))
+        Ok(Some((removed_save, (latest.clone()))))
     }
 
-    pub fn load_save(&self, index: usize) -> NSBoolResult {
+    // what loading the save would put at risk, for frontends that ask before calling `load_save`
+    pub fn load_warnings(&self, index: usize, mode: RestoreMode) -> NSResult<Vec<String>> {
+        match self.m_info.saves.get(index) {
+            Some(item) => Ok(self.load_warnings_of(item, mode)),
+            None => throw(&t!("invalid_index")),
+        }
+    }
+
+    fn load_warnings_of(&self, item: &SingleSave, mode: RestoreMode) -> Vec<String> {
+        // the mod config lives in the run part of the save
+        let mod_mismatch = if mode.includes_entry("mod_config.xml") {
+            self.mod_mismatch(item)
+        } else {
+            None
+        };
+        [self.game_build_mismatch(item), mod_mismatch]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    pub fn load_save(&self, index: usize, mode: RestoreMode) -> NSBoolResult {
         match self.m_info.saves.get(index) {
             Some(item) => {
-                if self
-                    .m_opm
-                    .confirm(t!("load_save_warning", save_name = item.get_name()).to_string())?
-                {
-                    self.m_file_operator
-                        .load_save(item.get_name().to_string())?;
+                let mut confirm_msg =
+                    t!("load_save_warning", save_name = item.get_name()).to_string();
+                if mode != RestoreMode::Full {
+                    confirm_msg += &format!(" ({})", mode);
+                }
+                // "save_shared" belongs to every slot, it's only restored with the whole save
+                let with_shared = mode == RestoreMode::Full && item.has_shared_settings();
+                if with_shared && self.is_game_running()? {
+                    return throw(&t!("shared_settings_game_running"));
+                }
+                if with_shared {
+                    confirm_msg.push('\n');
+                    confirm_msg += &t!("shared_settings_will_be_restored");
+                }
+                for warning in self.load_warnings_of(item, mode) {
+                    // the confirmation may never be shown, so the warnings must not depend on it
+                    if !self.m_opm.can_confirm() {
+                        self.m_opm.warning(warning.clone() + "\n");
+                    }
+                    confirm_msg.push('\n');
+                    confirm_msg += &warning;
+                }
+                if self.m_opm.confirm(confirm_msg)? {
+                    self.m_file_operator.load_save(
+                        item.get_name(),
+                        mode,
+                        &Self::filter_of(item)?,
+                    )?;
+                    if with_shared {
+                        self.m_file_operator.load_shared(item.get_name())?;
+                    }
                     Ok(true)
                 } else {
                     Ok(false)
@@ -321,6 +789,55 @@ impl<Opm: OutputManager> Core<Opm> {
         }
     }
 
+    // indexes of the saves that include a copy of "save_shared"
+    pub fn saves_with_shared_settings(&self) -> Vec<usize> {
+        self.m_info
+            .saves
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.has_shared_settings())
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    // restores only Noita's options and keybindings from a save, the slot is left untouched
+    pub fn restore_shared_settings(&self, index: usize) -> NSBoolResult {
+        let Some(item) = self.m_info.saves.get(index) else {
+            return throw(&t!("invalid_index"));
+        };
+        if !item.has_shared_settings() {
+            return throw(&t!(
+                "no_shared_settings_in_save",
+                save_name = item.get_name()
+            ));
+        }
+        if self.is_game_running()? {
+            return throw(&t!("shared_settings_game_running"));
+        }
+        if !self.m_opm.confirm(
+            t!(
+                "restore_shared_settings_warning",
+                save_name = item.get_name()
+            )
+            .to_string(),
+        )? {
+            return Ok(false);
+        }
+        self.m_file_operator.load_shared(item.get_name())?;
+        Ok(true)
+    }
+
+    // what loading the save with `mode` would replace, for a dry run
+    pub fn restore_plan(&self, index: usize, mode: RestoreMode) -> NSResult<RestorePlan> {
+        match self.m_info.saves.get(index) {
+            Some(item) => {
+                self.m_file_operator
+                    .restore_plan(item.get_name(), mode, &Self::filter_of(item)?)
+            }
+            None => throw(&t!("invalid_index")),
+        }
+    }
+
     #[inline]
     pub fn quick_load(&self) -> NSBoolResult {
         if self.m_info.saves.is_empty() {
@@ -329,10 +846,55 @@ impl<Opm: OutputManager> Core<Opm> {
                 operation = t!("load_operation")
             ))
         } else {
-            self.load_save(self.m_info.saves.len() - 1)
+            self.load_save(self.m_info.saves.len() - 1, RestoreMode::Full)
+        }
+    }
+
+    // parsed on demand, wand lists are too large to keep in "infos.json" for every save
+    pub fn inspect_wands(&self, index: usize) -> NSResult<Vec<WandSummary>> {
+        match self.m_info.saves.get(index) {
+            Some(item) => {
+                WandSummary::from_save(&self.m_file_operator.get_save_path(item.get_name()))
+            }
+            None => throw(&t!("invalid_index")),
         }
     }
 
+    // indexes of the saves in which the given perk has been picked up
+    pub fn find_saves_with_perk(&self, perk: &str) -> Vec<usize> {
+        self.m_info
+            .saves
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.get_perks().is_some_and(|perks| perks.has_perk(perk)))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    // indexes of the saves that belong to the run with the given seed
+    pub fn find_saves_of_run(&self, seed: u32) -> Vec<usize> {
+        self.m_info
+            .saves
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.get_seed() == Some(seed))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    // saves grouped by run in the order each run first appears, saves with an unknown seed
+    // are grouped under None
+    pub fn runs(&self) -> Vec<(Option<u32>, Vec<usize>)> {
+        let mut runs: Vec<(Option<u32>, Vec<usize>)> = Vec::new();
+        for (index, item) in self.m_info.saves.iter().enumerate() {
+            match runs.iter_mut().find(|(seed, _)| *seed == item.get_seed()) {
+                Some((_, indexes)) => indexes.push(index),
+                None => runs.push((item.get_seed(), vec![index])),
+            }
+        }
+        runs
+    }
+
     pub fn modify_save_info(
         &mut self,
         index: usize,
@@ -418,6 +980,65 @@ impl<Opm: OutputManager> Core<Opm> {
         self.delete_saves(vec![index])
     }
 
+    // indexes of the auto and quick saves that the retention policies would remove
+    pub fn prune_preview(&self) -> Vec<usize> {
+        let now = Local::now().timestamp();
+        let settings = self.m_info.get_settings();
+        let candidates_of = |kind: SaveKind| -> Vec<RetentionCandidate> {
+            self.m_info
+                .saves
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| item.get_kind() == kind)
+                .map(|(index, item)| RetentionCandidate {
+                    index,
+                    timestamp: item.get_timestamp(),
+                    is_locked: item.is_locked(),
+                })
+                .collect()
+        };
+
+        let mut expired = settings
+            .auto_save_retention
+            .expired(&candidates_of(SaveKind::Auto), now);
+        expired.extend(
+            settings
+                .quick_save_retention
+                .expired(&candidates_of(SaveKind::Quick), now),
+        );
+        expired.sort_unstable();
+        expired
+    }
+
+    // returns the indexes that were (or with `dry_run`, would be) removed
+    pub fn prune(&mut self, dry_run: bool) -> NSResult<Vec<usize>> {
+        let indexes = self.prune_preview();
+        if indexes.is_empty() {
+            self.m_opm.log(t!("no_save_to_prune").to_string() + "\n");
+            return Ok(indexes);
+        }
+        if dry_run {
+            let mut msg = t!("prune_dry_run_list").to_string();
+            msg.push_str(":\n");
+            for &index in indexes.iter() {
+                let item = &self.m_info.saves[index];
+                msg += &format!(
+                    "[{}]  {}\t\t{}\n",
+                    index + 1,
+                    item.get_name(),
+                    item.get_note()
+                );
+            }
+            self.m_opm.log(msg);
+            return Ok(indexes);
+        }
+        if self.delete_saves(indexes.clone())? {
+            Ok(indexes)
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
     pub fn lock(&mut self, indexes: Vec<usize>) -> NSComResult {
         let mut suc_msg = t!("lock_suc_msg").to_string();
         suc_msg.push_str(":\n");
@@ -473,6 +1094,313 @@ impl<Opm: OutputManager> Core<Opm> {
         Ok(())
     }
 
+    #[inline]
+    pub fn get_mod_profiles(&self) -> &[ModProfile] {
+        self.m_info.get_mod_profiles()
+    }
+
+    // captures the mods enabled in the live save, replacing the profile with the same name
+    pub fn capture_mod_profile(&mut self, name: String) -> NSBoolResult {
+        Self::check_profile_name(&name)?;
+        let Some(mods) = ModList::from_save(self.m_file_operator.get_live_save_path())? else {
+            return throw(&t!("no_mod_config"));
+        };
+        let exists = self
+            .m_info
+            .get_mod_profiles()
+            .iter()
+            .any(|profile| profile.m_name == name);
+        if exists
+            && !self
+                .m_opm
+                .confirm(t!("overwrite_mod_profile_warning", profile_name = name).to_string())?
+        {
+            return Ok(false);
+        }
+
+        let profile = ModProfile {
+            m_has_settings: self.m_file_operator.save_mod_settings(&name)?,
+            m_name: name,
+            m_mods: mods,
+        };
+        let profiles = self.m_info.get_mod_profiles_mut();
+        match profiles
+            .iter_mut()
+            .find(|item| item.m_name == profile.m_name)
+        {
+            Some(item) => *item = profile,
+            None => profiles.push(profile),
+        }
+        self.write_infos()?;
+        Ok(true)
+    }
+
+    // rewrites the live "mod_config.xml" to enable the profile's mods, the previous config is
+    // backed up first
+    pub fn apply_mod_profile(&self, name: &str) -> NSComResult {
+        if self.is_game_running()? {
+            return throw(&t!("mod_profile_game_running"));
+        }
+        let Some(profile) = self
+            .m_info
+            .get_mod_profiles()
+            .iter()
+            .find(|profile| profile.m_name == name)
+        else {
+            return throw(&t!("unknown_mod_profile", profile_name = name));
+        };
+
+        let mod_config = self.m_file_operator.read_mod_config()?;
+        let (mod_config, missing) = profile.m_mods.apply_to_config(&mod_config)?;
+        let backup_dir = self
+            .m_file_operator
+            .backup_mod_config(&Local::now().format("%Y%m%d_%H%M%S").to_string())?;
+        self.m_opm.log(
+            t!("mod_config_backed_up", path = backup_dir.to_string_lossy()).to_string() + "\n",
+        );
+        if !missing.is_empty() {
+            self.m_opm.warning(
+                t!("mod_profile_mods_not_installed").to_string()
+                    + &format!(": {}\n", missing.join(", ")),
+            );
+        }
+        self.m_file_operator
+            .write_mod_config(&mod_config, profile.m_has_settings.then_some(name))
+    }
+
+    pub fn delete_mod_profile(&mut self, name: &str) -> NSBoolResult {
+        if !self
+            .m_info
+            .get_mod_profiles()
+            .iter()
+            .any(|profile| profile.m_name == name)
+        {
+            return throw(&t!("unknown_mod_profile", profile_name = name));
+        }
+        if !self
+            .m_opm
+            .confirm(t!("delete_mod_profile_warning", profile_name = name).to_string())?
+        {
+            return Ok(false);
+        }
+        self.m_file_operator.remove_mod_settings(name)?;
+        self.m_info
+            .get_mod_profiles_mut()
+            .retain(|profile| profile.m_name != name);
+        self.write_infos()?;
+        Ok(true)
+    }
+
+    #[inline]
+    pub fn get_progress_profiles(&self) -> &[ProgressProfile] {
+        self.m_info.get_progress_profiles()
+    }
+
+    // profiles are stored as files and folders named after them
+    fn check_profile_name(name: &str) -> NSComResult {
+        if name.trim().is_empty() {
+            return throw(&t!("profile_name_empty"));
+        }
+        if name.starts_with('.') || name.contains(['/', '\\', ':']) {
+            return throw(&t!("invalid_profile_name", profile_name = name));
+        }
+        Ok(())
+    }
+
+    // copies the live "persistent" folder into the profile, replacing the one with the same name
+    pub fn capture_progress_profile(&mut self, name: String) -> NSBoolResult {
+        Self::check_profile_name(&name)?;
+        let exists = self
+            .m_info
+            .get_progress_profiles()
+            .iter()
+            .any(|profile| profile.m_name == name);
+        if exists
+            && !self.m_opm.confirm(
+                t!("overwrite_progress_profile_warning", profile_name = name).to_string(),
+            )?
+        {
+            return Ok(false);
+        }
+
+        self.m_file_operator.save_progress_profile(&name)?;
+        let profile = ProgressProfile {
+            m_summary: ProgressSummary::from_dir(
+                &self.m_file_operator.get_progress_profile_path(&name),
+            )?,
+            m_timestamp: Local::now().timestamp(),
+            m_name: name,
+        };
+        let profiles = self.m_info.get_progress_profiles_mut();
+        match profiles
+            .iter_mut()
+            .find(|item| item.m_name == profile.m_name)
+        {
+            Some(item) => *item = profile,
+            None => profiles.push(profile),
+        }
+        self.write_infos()?;
+        Ok(true)
+    }
+
+    // replaces the live "persistent" folder with the profile, the previous one is backed up first
+    pub fn switch_progress_profile(&self, name: &str) -> NSBoolResult {
+        if self.is_game_running()? {
+            return throw(&t!("progress_profile_game_running"));
+        }
+        if !self
+            .m_info
+            .get_progress_profiles()
+            .iter()
+            .any(|profile| profile.m_name == name)
+        {
+            return throw(&t!("unknown_progress_profile", profile_name = name));
+        }
+        if !self
+            .m_opm
+            .confirm(t!("switch_progress_profile_warning", profile_name = name).to_string())?
+        {
+            return Ok(false);
+        }
+        let backup_dir = self
+            .m_file_operator
+            .switch_progress(name, &Local::now().format("%Y%m%d_%H%M%S").to_string())?;
+        self.m_opm
+            .log(t!("progress_backed_up", path = backup_dir.to_string_lossy()).to_string() + "\n");
+        Ok(true)
+    }
+
+    // what changes going from progress `old` to `new`, None stands for the live progress
+    pub fn compare_progress(&self, old: Option<&str>, new: Option<&str>) -> NSResult<ProgressDiff> {
+        let summary_of = |name: Option<&str>| -> NSResult<ProgressSummary> {
+            match name {
+                Some(name) => {
+                    if !self
+                        .m_info
+                        .get_progress_profiles()
+                        .iter()
+                        .any(|profile| profile.m_name == name)
+                    {
+                        return throw(&t!("unknown_progress_profile", profile_name = name));
+                    }
+                    ProgressSummary::from_dir(&self.m_file_operator.get_progress_profile_path(name))
+                }
+                None => ProgressSummary::from_dir(&self.m_file_operator.get_live_progress_path()),
+            }
+        };
+        Ok(summary_of(old)?.diff(&summary_of(new)?))
+    }
+
+    pub fn delete_progress_profile(&mut self, name: &str) -> NSBoolResult {
+        if !self
+            .m_info
+            .get_progress_profiles()
+            .iter()
+            .any(|profile| profile.m_name == name)
+        {
+            return throw(&t!("unknown_progress_profile", profile_name = name));
+        }
+        if !self
+            .m_opm
+            .confirm(t!("delete_progress_profile_warning", profile_name = name).to_string())?
+        {
+            return Ok(false);
+        }
+        self.m_file_operator.remove_progress_profile(name)?;
+        self.m_info
+            .get_progress_profiles_mut()
+            .retain(|profile| profile.m_name != name);
+        self.write_infos()?;
+        Ok(true)
+    }
+
+    // path of the save's minimap, rendered on first request and cached next to the save,
+    // None if the save holds no explored chunk
+    pub fn minimap(&self, index: usize) -> NSResult<Option<PathBuf>> {
+        let Some(item) = self.m_info.saves.get(index) else {
+            return throw(&t!("invalid_index"));
+        };
+        let preview_path = self.m_file_operator.preview_path(item.get_name());
+        if preview_path.exists() {
+            return Ok(Some(preview_path));
+        }
+        let player = item.get_player().map(|player| (player.m_x, player.m_y));
+        let save_path = self.m_file_operator.get_save_path(item.get_name());
+        if render_minimap(&save_path, player, &preview_path)? {
+            Ok(Some(preview_path))
+        } else {
+            Ok(None)
+        }
+    }
+
+    // compares the live save with the newest snapshot, cheap enough to run before every prompt
+    pub fn live_status(&self) -> NSResult<LiveStatus> {
+        let Some(latest) = self.m_info.saves.last() else {
+            return Ok(LiveStatus::NoSnapshot);
+        };
+        let name = latest.get_name().to_string();
+        Ok(
+            match self
+                .m_file_operator
+                .live_unchanged_since(&name, &Self::filter_of(latest)?)?
+            {
+                None => LiveStatus::Unknown { m_name: name },
+                Some(true) => LiveStatus::InSync { m_name: name },
+                Some(false) => LiveStatus::ModifiedSince {
+                    m_name: name,
+                    m_timestamp: latest.get_timestamp(),
+                },
+            },
+        )
+    }
+
+    // what changed going from save `old` to save `new`, None stands for the live save
+    pub fn diff(&self, old: Option<usize>, new: Option<usize>) -> NSResult<SaveDiff> {
+        let save_of = |index: Option<usize>| -> NSResult<Option<&SingleSave>> {
+            match index {
+                Some(index) => match self.m_info.saves.get(index) {
+                    Some(item) => Ok(Some(item)),
+                    None => throw(&t!("invalid_index")),
+                },
+                None => Ok(None),
+            }
+        };
+        let (old, new) = (save_of(old)?, save_of(new)?);
+        // a live save compared with a snapshot only has the files the snapshot would copy
+        let filter = old
+            .or(new)
+            .map_or(Ok(PathFilter::default()), Self::filter_of)?;
+        let (old, new) = (old.map(SingleSave::get_name), new.map(SingleSave::get_name));
+        let dir_of = |folder: Option<&str>| match folder {
+            Some(folder) => self.m_file_operator.get_save_path(folder),
+            None => self.m_file_operator.get_live_save_path().to_path_buf(),
+        };
+        Ok(SaveDiff {
+            m_files: self.m_file_operator.diff_files(old, new, &filter)?,
+            m_state: GameStateDiff::between(&dir_of(old), &dir_of(new))?,
+        })
+    }
+
+    // run history from the live save and every snapshot
+    pub fn stats(&self) -> NSResult<StatsReport> {
+        let snapshots: Vec<PathBuf> = self
+            .m_info
+            .saves
+            .iter()
+            .map(|item| self.m_file_operator.get_save_path(item.get_name()))
+            .collect();
+        StatsReport::from_saves(
+            std::iter::once(self.m_file_operator.get_live_save_path())
+                .chain(snapshots.iter().map(PathBuf::as_path)),
+            |path, e| {
+                self.m_opm.warning(
+                    t!("skip_malformed_session_stats", path = format!("{path:?}")).to_string()
+                        + &format!(": {}\n", e.get_explanation().join(", ")),
+                );
+            },
+        )
+    }
+
     #[inline]
     pub fn usage_by_mb(&self) -> NSResult<f64> {
         self.m_file_operator.get_usage()
@@ -0,0 +1,91 @@
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+// how often the game process is checked while following the game
+const GAME_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoSaveEvent {
+    Idle,
+    Save,
+    // the last snapshot taken a while after the game exits
+    FinalSave,
+    GameStarted,
+    GameExited,
+}
+
+// decides when timed auto save should run, the caller does the waiting and the saving
+#[derive(Debug)]
+pub struct AutoSaveScheduler {
+    m_interval: Duration,
+    m_only_in_game: bool,
+    m_final_delay: Duration,
+    m_last_save: Instant,
+    m_game_running: bool,
+    m_exited_at: Option<Instant>,
+}
+
+impl AutoSaveScheduler {
+    pub fn new(
+        interval: Duration,
+        only_in_game: bool,
+        final_delay: Duration,
+        game_running: bool,
+    ) -> Self {
+        Self {
+            m_interval: interval,
+            m_only_in_game: only_in_game,
+            m_final_delay: final_delay,
+            m_last_save: Instant::now(),
+            m_game_running: game_running,
+            m_exited_at: None,
+        }
+    }
+
+    #[inline]
+    pub fn poll_interval(&self) -> Duration {
+        if self.m_only_in_game {
+            GAME_POLL_INTERVAL.min(self.m_interval)
+        } else {
+            self.m_interval
+        }
+    }
+
+    // `is_game_running` is only called when following the game
+    pub fn tick(&mut self, is_game_running: impl FnOnce() -> bool) -> AutoSaveEvent {
+        let now = Instant::now();
+        if !self.m_only_in_game {
+            return if now.duration_since(self.m_last_save) >= self.m_interval {
+                self.m_last_save = now;
+                AutoSaveEvent::Save
+            } else {
+                AutoSaveEvent::Idle
+            };
+        }
+
+        let running = is_game_running();
+        let was_running = std::mem::replace(&mut self.m_game_running, running);
+        if running && !was_running {
+            self.m_exited_at = None;
+            self.m_last_save = now;
+            return AutoSaveEvent::GameStarted;
+        }
+        if !running && was_running {
+            self.m_exited_at = Some(now);
+            return AutoSaveEvent::GameExited;
+        }
+        if let Some(exited_at) = self.m_exited_at
+            && now.duration_since(exited_at) >= self.m_final_delay
+        {
+            self.m_exited_at = None;
+            self.m_last_save = now;
+            return AutoSaveEvent::FinalSave;
+        }
+        if running && now.duration_since(self.m_last_save) >= self.m_interval {
+            self.m_last_save = now;
+            return AutoSaveEvent::Save;
+        }
+        AutoSaveEvent::Idle
+    }
+}
@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::error::*;
+use super::game_state::perks::PerkSummary;
+use super::game_state::player::PlayerStats;
+use super::game_state::wands::WandSummary;
+use super::manifest::FileDiff;
+
+// a wand that was added, removed or rebuilt, matched by its quick inventory slot
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WandChange {
+    pub m_slot: u32,
+    pub m_before: Option<WandSummary>,
+    pub m_after: Option<WandSummary>,
+}
+
+// every field is None (or empty) when it didn't change
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct GameStateDiff {
+    pub m_hp: Option<(f64, f64)>,
+    pub m_max_hp: Option<(f64, f64)>,
+    pub m_gold: Option<(i64, i64)>,
+    pub m_position: Option<((f64, f64), (f64, f64))>,
+    pub m_wands: Vec<WandChange>,
+    pub m_perks_gained: Vec<String>,
+    pub m_perks_lost: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SaveDiff {
+    pub m_files: FileDiff,
+    pub m_state: GameStateDiff,
+}
+
+fn changed<T: PartialEq>(before: Option<T>, after: Option<T>) -> Option<(T, T)> {
+    match (before, after) {
+        (Some(before), Some(after)) if before != after => Some((before, after)),
+        _ => None,
+    }
+}
+
+impl GameStateDiff {
+    pub fn between(old_dir: &Path, new_dir: &Path) -> NSResult<Self> {
+        let (old_player, new_player) = (
+            PlayerStats::from_save(old_dir)?,
+            PlayerStats::from_save(new_dir)?,
+        );
+        let (old_perks, new_perks) = (
+            PerkSummary::from_save(old_dir)?.unwrap_or_default(),
+            PerkSummary::from_save(new_dir)?.unwrap_or_default(),
+        );
+        let player_field =
+            |player: &Option<PlayerStats>, f: fn(&PlayerStats) -> f64| player.as_ref().map(f);
+        let perk_ids = |perks: &PerkSummary| -> Vec<String> {
+            perks.m_perks.iter().map(|perk| perk.m_id.clone()).collect()
+        };
+        let (old_ids, new_ids) = (perk_ids(&old_perks), perk_ids(&new_perks));
+
+        Ok(Self {
+            m_hp: changed(
+                player_field(&old_player, |player| player.m_hp.round()),
+                player_field(&new_player, |player| player.m_hp.round()),
+            ),
+            m_max_hp: changed(
+                player_field(&old_player, |player| player.m_max_hp.round()),
+                player_field(&new_player, |player| player.m_max_hp.round()),
+            ),
+            m_gold: changed(
+                old_player.as_ref().map(|player| player.m_gold),
+                new_player.as_ref().map(|player| player.m_gold),
+            ),
+            m_position: changed(
+                old_player
+                    .as_ref()
+                    .map(|player| (player.m_x.round(), player.m_y.round())),
+                new_player
+                    .as_ref()
+                    .map(|player| (player.m_x.round(), player.m_y.round())),
+            ),
+            m_wands: Self::wand_changes(
+                WandSummary::from_save(old_dir)?,
+                WandSummary::from_save(new_dir)?,
+            ),
+            m_perks_gained: new_ids
+                .iter()
+                .filter(|id| !old_ids.contains(id))
+                .cloned()
+                .collect(),
+            m_perks_lost: old_ids
+                .iter()
+                .filter(|id| !new_ids.contains(id))
+                .cloned()
+                .collect(),
+        })
+    }
+
+    fn wand_changes(before: Vec<WandSummary>, after: Vec<WandSummary>) -> Vec<WandChange> {
+        let mut slots: Vec<u32> = before
+            .iter()
+            .chain(after.iter())
+            .map(|wand| wand.m_slot)
+            .collect();
+        slots.sort();
+        slots.dedup();
+        slots
+            .into_iter()
+            .filter_map(|slot| {
+                let old = before.iter().find(|wand| wand.m_slot == slot);
+                let new = after.iter().find(|wand| wand.m_slot == slot);
+                if let (Some(old), Some(new)) = (old, new)
+                    && old.same_build(new)
+                {
+                    return None;
+                }
+                Some(WandChange {
+                    m_slot: slot,
+                    m_before: old.cloned(),
+                    m_after: new.cloned(),
+                })
+            })
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.m_hp.is_none()
+            && self.m_max_hp.is_none()
+            && self.m_gold.is_none()
+            && self.m_position.is_none()
+            && self.m_wands.is_empty()
+            && self.m_perks_gained.is_empty()
+            && self.m_perks_lost.is_empty()
+    }
+}
+
+// "{}" only counts the changed files, "{:#}" lists them
+impl std::fmt::Display for SaveDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let files = &self.m_files;
+        writeln!(
+            f,
+            "{}: +{} -{} ~{}",
+            t!("diff_files"),
+            files.m_added.len(),
+            files.m_removed.len(),
+            files.m_modified.len()
+        )?;
+        if f.alternate() {
+            for (sign, paths) in [
+                ('+', &files.m_added),
+                ('-', &files.m_removed),
+                ('~', &files.m_modified),
+            ] {
+                for path in paths {
+                    writeln!(f, "    {sign} {path}")?;
+                }
+            }
+        }
+
+        let state = &self.m_state;
+        if state.is_empty() {
+            return writeln!(f, "{}", t!("diff_state_unchanged"));
+        }
+        if let Some((before, after)) = state.m_hp {
+            writeln!(f, "{}: {:.0} -> {:.0}", t!("stat_hp"), before, after)?;
+        }
+        if let Some((before, after)) = state.m_max_hp {
+            writeln!(f, "{}: {:.0} -> {:.0}", t!("diff_max_hp"), before, after)?;
+        }
+        if let Some((before, after)) = state.m_gold {
+            writeln!(f, "{}: {} -> {}", t!("stat_gold"), before, after)?;
+        }
+        if let Some(((x1, y1), (x2, y2))) = state.m_position {
+            writeln!(
+                f,
+                "{}: ({:.0}, {:.0}) -> ({:.0}, {:.0})",
+                t!("stat_position"),
+                x1,
+                y1,
+                x2,
+                y2
+            )?;
+        }
+        if !state.m_perks_gained.is_empty() {
+            writeln!(
+                f,
+                "{}: {}",
+                t!("diff_perks_gained"),
+                state.m_perks_gained.join(", ")
+            )?;
+        }
+        if !state.m_perks_lost.is_empty() {
+            writeln!(
+                f,
+                "{}: {}",
+                t!("diff_perks_lost"),
+                state.m_perks_lost.join(", ")
+            )?;
+        }
+        for change in &state.m_wands {
+            let status = match (&change.m_before, &change.m_after) {
+                (None, Some(_)) => t!("diff_wand_added"),
+                (Some(_), None) => t!("diff_wand_removed"),
+                _ => t!("diff_wand_changed"),
+            };
+            writeln!(f, "{} [{}]: {}", t!("diff_wand"), change.m_slot + 1, status)?;
+            if f.alternate() {
+                if let Some(before) = &change.m_before {
+                    writeln!(f, "  - {}", before)?;
+                }
+                if let Some(after) = &change.m_after {
+                    writeln!(f, "  + {}", after)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -14,6 +14,9 @@ enum ErrorType {
     Serialize(serde_json::Error),
     Mutex(String),
     Regex(regex::Error),
+    Glob(glob::PatternError),
+    Xml(roxmltree::Error),
+    Png(png::EncodingError),
 }
 
 #[derive(Debug)]
@@ -32,6 +35,9 @@ impl std::error::Error for NSError {
             Serialize(ref err) => Some(err),
             Mutex(..) => None,
             Regex(ref err) => Some(err),
+            Glob(ref err) => Some(err),
+            Xml(ref err) => Some(err),
+            Png(ref err) => Some(err),
         }
     }
 }
@@ -122,6 +128,9 @@ impl std::fmt::Display for ErrorType {
             Serialize(..) => "SerializeError",
             Mutex(msg) => &format!("MutexError({})", msg),
             Regex(..) => "RegexError",
+            Glob(..) => "GlobError",
+            Xml(..) => "XmlError",
+            Png(..) => "PngError",
         };
         write!(f, "{}", msg)
     }
@@ -174,6 +183,36 @@ impl From<regex::Error> for NSError {
     }
 }
 
+impl From<glob::PatternError> for NSError {
+    fn from(value: glob::PatternError) -> Self {
+        NSError {
+            m_explanation: Vec::new(),
+            m_err_type: ErrorType::Glob(value),
+            m_isfatal: false,
+        }
+    }
+}
+
+impl From<roxmltree::Error> for NSError {
+    fn from(value: roxmltree::Error) -> Self {
+        NSError {
+            m_explanation: Vec::new(),
+            m_err_type: ErrorType::Xml(value),
+            m_isfatal: false,
+        }
+    }
+}
+
+impl From<png::EncodingError> for NSError {
+    fn from(value: png::EncodingError) -> Self {
+        NSError {
+            m_explanation: Vec::new(),
+            m_err_type: ErrorType::Png(value),
+            m_isfatal: false,
+        }
+    }
+}
+
 // ------------------- Serialize (for backend-frontend communication of GUI Application) ----------------
 impl Serialize for NSError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
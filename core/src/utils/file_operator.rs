@@ -5,16 +5,29 @@ use std::io::{Seek, Write};
 use std::path::{Path, PathBuf};
 
 use super::error::*;
+use super::manifest::{FileDiff, Manifest};
+use super::restore_mode::{PROGRESS_FOLDER, RestoreMode, RestorePlan};
 use super::save_infos::AllInfos;
+#[cfg(target_os = "linux")]
+use super::settings::Settings;
+use super::snapshot_filter::PathFilter;
 
 const SAVE_INFO_PATH_POSTFIX: &str = r"infos.json";
+const MANIFEST_FOLDER: &str = r".manifests";
+const PREVIEW_FOLDER: &str = r".previews";
+const SHARED_FOLDER: &str = r".shared";
+// next to "save00", holds the options and keybindings shared by every slot
+const NOITA_SHARED_FOLDER: &str = r"save_shared";
+const MOD_PROFILE_FOLDER: &str = r".mod_profiles";
+const MOD_CONFIG_BACKUP_FOLDER: &str = r".mod_config_backups";
+const PROGRESS_PROFILE_FOLDER: &str = r".progress_profiles";
+const PROGRESS_BACKUP_FOLDER: &str = r".progress_backups";
+const MOD_CONFIG_FILE: &str = r"mod_config.xml";
+const MOD_SETTINGS_FILE: &str = r"mod_settings.bin";
 
 #[cfg(target_os = "windows")]
 const NOITA_SAVE_PATH_POSTFIX: &str = r"Appdata\LocalLow\Nolla_Games_Noita\save00";
 
-#[cfg(target_os = "linux")]
-const NOITA_SAVE_PATH_POSTFIX: &str = r".local/share/Steam/steamapps/compatdata/881100/pfx/drive_c/users/steamuser/AppData/LocalLow/Nolla_Games_Noita/save00";
-
 #[derive(Debug)]
 pub struct FileOperator {
     m_file: fs::File,
@@ -23,16 +36,29 @@ pub struct FileOperator {
 }
 
 impl FileOperator {
-    pub fn new() -> NSResult<Self> {
-        let save_folder_path = Self::get_save_folder_path()?;
+    // `noita_save_path` is the live "save00", on Linux it depends on the detected Steam layout
+    pub fn new(noita_save_path: PathBuf) -> NSResult<Self> {
+        Self::with_save_folder(noita_save_path, Self::get_save_folder_path()?)
+    }
+
+    // `save_folder_path` is where the snapshots and "infos.json" are kept
+    fn with_save_folder(noita_save_path: PathBuf, save_folder_path: PathBuf) -> NSResult<Self> {
         Ok(Self {
             m_file: Self::open_info_file(&save_folder_path)?,
-            m_noita_save_path: Self::get_noita_save_path()?,
+            m_noita_save_path: noita_save_path,
             m_save_folder_path: save_folder_path,
         })
     }
 
-    fn get_noita_save_path() -> NSResult<PathBuf> {
+    // the live "save00" moves along when the settings pick another Steam install
+    #[cfg(target_os = "linux")]
+    #[inline]
+    pub fn set_noita_save_path(&mut self, noita_save_path: PathBuf) {
+        self.m_noita_save_path = noita_save_path;
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn get_noita_save_path() -> NSResult<PathBuf> {
         if let Some(home_dir) = BaseDirs::new() {
             Ok(home_dir.home_dir().join(NOITA_SAVE_PATH_POSTFIX))
         } else {
@@ -40,6 +66,18 @@ impl FileOperator {
         }
     }
 
+    // the settings as last written, read before the info file is opened since on Linux they
+    // decide where the live save is, a file that can't be read is left to `load_infos`
+    #[cfg(target_os = "linux")]
+    pub fn read_settings() -> NSResult<Settings> {
+        let info_path = Self::get_save_folder_path()?.join(SAVE_INFO_PATH_POSTFIX);
+        Ok(fs::read_to_string(info_path)
+            .ok()
+            .and_then(|text| serde_json::from_str::<AllInfos>(&text).ok())
+            .map(|infos| infos.get_settings().clone())
+            .unwrap_or_default())
+    }
+
     fn get_save_folder_path() -> NSResult<PathBuf> {
         #[cfg(target_os = "windows")]
         return Ok(PathBuf::from(r".\Saves\"));
@@ -77,7 +115,13 @@ impl FileOperator {
         Ok(f)
     }
 
-    fn copy_dir(src: &Path, dst: &Path) -> NSComResult {
+    // copies the files of `src` that `filter` keeps, judged by their path relative to `src`
+    fn copy_dir(src: &Path, dst: &Path, filter: &PathFilter) -> NSComResult {
+        Self::copy_dir_below(src, src, dst, filter)
+    }
+
+    // like `copy_dir` for a folder inside `root`, `filter` still sees paths relative to `root`
+    fn copy_dir_below(root: &Path, src: &Path, dst: &Path, filter: &PathFilter) -> NSComResult {
         if !src.exists() {
             return throw(&t!("source_path_not_exist", path = src.to_str().unwrap()));
         }
@@ -88,20 +132,45 @@ impl FileOperator {
         for entry in fs::read_dir(src)? {
             let dir_entry = entry?;
             if dir_entry.file_type()?.is_dir() {
-                Self::copy_dir(
+                Self::copy_dir_below(
+                    root,
                     &dir_entry.path(),
                     &dst.join(dir_entry.path().file_name().unwrap()),
+                    filter,
                 )?;
-            } else {
-                fs::copy(
-                    dir_entry.path(),
-                    dst.join(dir_entry.path().file_name().unwrap()),
+            } else if filter.keeps(&Manifest::relative_key(root, &dir_entry.path())) {
+                Self::copy_file(
+                    &dir_entry.path(),
+                    &dst.join(dir_entry.path().file_name().unwrap()),
                 )?;
             }
         }
         Ok(())
     }
 
+    // `path` itself if it's free, otherwise with the first free "_<n>" suffix
+    fn unused_path(path: PathBuf) -> PathBuf {
+        if !path.exists() {
+            return path;
+        }
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        (2..)
+            .map(|n| path.with_file_name(format!("{name}_{n}")))
+            .find(|candidate| !candidate.exists())
+            .unwrap()
+    }
+
+    // keeps the modification time, manifests recorded from the live save compare by it
+    fn copy_file(src: &Path, dst: &Path) -> NSComResult {
+        fs::copy(src, dst)?;
+        let modified = fs::metadata(src)?.modified()?;
+        fs::File::options()
+            .write(true)
+            .open(dst)?
+            .set_modified(modified)?;
+        Ok(())
+    }
+
     pub fn load_infos(&self) -> NSResult<AllInfos> {
         let infos: AllInfos = serde_json::from_reader(std::io::BufReader::new(&self.m_file))
             .explain_fatal(&t!("fail_parse_save_info_file"))?;
@@ -124,34 +193,467 @@ impl FileOperator {
         Ok(())
     }
 
-    pub fn save(&self, folder_name: &str) -> NSComResult {
+    #[inline]
+    pub fn get_save_path(&self, folder_name: &str) -> PathBuf {
+        self.m_save_folder_path.join(folder_name)
+    }
+
+    #[inline]
+    pub fn get_live_save_path(&self) -> &Path {
+        &self.m_noita_save_path
+    }
+
+    fn manifest_path(&self, folder_name: &str) -> PathBuf {
+        self.m_save_folder_path
+            .join(MANIFEST_FOLDER)
+            .join(format!("{folder_name}.json"))
+    }
+
+    // the cached minimap of a snapshot
+    pub fn preview_path(&self, folder_name: &str) -> PathBuf {
+        self.m_save_folder_path
+            .join(PREVIEW_FOLDER)
+            .join(format!("{folder_name}.png"))
+    }
+
+    fn mod_settings_path(&self, profile_name: &str) -> PathBuf {
+        self.m_save_folder_path
+            .join(MOD_PROFILE_FOLDER)
+            .join(format!("{profile_name}.bin"))
+    }
+
+    // the snapshot's copy of "save_shared"
+    fn shared_path(&self, folder_name: &str) -> PathBuf {
+        self.m_save_folder_path
+            .join(SHARED_FOLDER)
+            .join(folder_name)
+    }
+
+    fn live_shared_path(&self) -> PathBuf {
+        self.m_noita_save_path
+            .parent()
+            .unwrap_or(&self.m_noita_save_path)
+            .join(NOITA_SHARED_FOLDER)
+    }
+
+    // files and folders kept next to a snapshot that have to follow it when it's renamed or removed
+    fn side_files(&self, folder_name: &str) -> [PathBuf; 3] {
+        [
+            self.manifest_path(folder_name),
+            self.preview_path(folder_name),
+            self.shared_path(folder_name),
+        ]
+    }
+
+    // refuses to start if the disk holding the saves can't fit a full copy of the live save
+    fn check_free_space(&self) -> NSComResult {
+        // nothing to measure, copying the missing save reports the real problem
+        if !self.m_noita_save_path.exists() {
+            return Ok(());
+        }
+        let needed = Self::caculate_usage(&self.m_noita_save_path)?;
+        let available = fs2::available_space(&self.m_save_folder_path)
+            .explain(&t!("fail_query_free_space"))? as f64
+            / 1_048_576f64;
+        if needed > available {
+            return throw(&t!(
+                "not_enough_disk_space",
+                needed = format!("{needed:.2}"),
+                available = format!("{available:.2}")
+            ));
+        }
+        Ok(())
+    }
+
+    // only the live files `filter` keeps are copied and listed in the manifest
+    pub fn save(&self, folder_name: &str, with_hashes: bool, filter: &PathFilter) -> NSComResult {
+        self.check_free_space()?;
         let dst = self.m_save_folder_path.join(folder_name);
-        Self::copy_dir(&self.m_noita_save_path, &dst).explain(&t!("fail_save_achive"))?;
+        // built before copying, so that anything written during the copy counts as a change
+        let manifest = Manifest::build(&self.m_noita_save_path, with_hashes, filter)
+            .explain(&t!("fail_save_achive"))?;
+        Self::copy_dir(&self.m_noita_save_path, &dst, filter).explain(&t!("fail_save_achive"))?;
+        manifest.write(&self.manifest_path(folder_name))?;
+        // an overwritten snapshot must not keep the preview of its old content
+        let preview_path = self.preview_path(folder_name);
+        if preview_path.exists() {
+            fs::remove_file(preview_path).explain(&t!("fail_save_achive"))?;
+        }
         Ok(())
     }
 
+    // true if the live save still matches the manifest recorded for `folder_name`,
+    // `filter` is the one the snapshot was taken with
+    pub fn live_matches_snapshot(&self, folder_name: &str, filter: &PathFilter) -> NSBoolResult {
+        Ok(self
+            .live_unchanged_since(folder_name, filter)?
+            .unwrap_or(false))
+    }
+
+    // like `live_matches_snapshot`, None if no manifest was recorded for `folder_name`
+    pub fn live_unchanged_since(
+        &self,
+        folder_name: &str,
+        filter: &PathFilter,
+    ) -> NSResult<Option<bool>> {
+        let Some(recorded) = Manifest::load(&self.manifest_path(folder_name))? else {
+            return Ok(None);
+        };
+        Ok(Some(
+            recorded
+                .matches_tree(&self.m_noita_save_path, filter)
+                .explain(&t!("fail_scan_live_save"))?,
+        ))
+    }
+
+    // files that changed going from `old` to `new`, None stands for the live save,
+    // `filter` applies to the trees scanned here instead of compared by their recorded manifest
+    pub fn diff_files(
+        &self,
+        old: Option<&str>,
+        new: Option<&str>,
+        filter: &PathFilter,
+    ) -> NSResult<FileDiff> {
+        let recorded = |folder: Option<&str>| match folder {
+            Some(folder) => Manifest::load(&self.manifest_path(folder)),
+            None => Ok(None),
+        };
+        let (old_recorded, new_recorded) = (recorded(old)?, recorded(new)?);
+        // a snapshot's own mtimes are the time it was copied, only the manifest recorded
+        // while taking it keeps the mtimes of the live save, without one compare contents
+        let usable =
+            (old.is_none() || old_recorded.is_some()) && (new.is_none() || new_recorded.is_some());
+        let with_hashes = !usable
+            || old_recorded.as_ref().is_some_and(Manifest::has_hashes)
+            || new_recorded.as_ref().is_some_and(Manifest::has_hashes);
+        let manifest_of = |folder: Option<&str>, recorded: Option<Manifest>| match recorded {
+            Some(manifest) if usable => Ok(manifest),
+            _ => Manifest::build(
+                &folder.map_or(self.m_noita_save_path.clone(), |folder| {
+                    self.get_save_path(folder)
+                }),
+                with_hashes,
+                filter,
+            )
+            .explain(&t!("fail_scan_save_tree")),
+        };
+        Ok(manifest_of(old, old_recorded)?.diff(&manifest_of(new, new_recorded)?))
+    }
+
     pub fn remove_save(&self, folder_name: &str) -> NSComResult {
         fs::remove_dir_all(self.m_save_folder_path.join(folder_name))
             .explain(&t!("fail_remove_folder", folder_name = folder_name))?;
+        for path in self.side_files(folder_name) {
+            if path.is_dir() {
+                fs::remove_dir_all(path)
+                    .explain(&t!("fail_remove_folder", folder_name = folder_name))?;
+            } else if path.exists() {
+                fs::remove_file(path)
+                    .explain(&t!("fail_remove_folder", folder_name = folder_name))?;
+            }
+        }
         Ok(())
     }
 
-    pub fn load_save(&self, folder_name: String) -> NSComResult {
-        fs::remove_dir_all(&self.m_noita_save_path).explain(&t!("fail_remove_crr_noita_save"))?;
-        Self::copy_dir(
-            &self.m_save_folder_path.join(folder_name),
-            &self.m_noita_save_path,
-        )
-        .explain(&t!("fail_load_save"))?;
+    // `filter` is the one the snapshot was taken with, live files it doesn't keep are left as
+    // they are since the snapshot holds nothing to replace them with
+    pub fn load_save(
+        &self,
+        folder_name: &str,
+        mode: RestoreMode,
+        filter: &PathFilter,
+    ) -> NSComResult {
+        let src = self.m_save_folder_path.join(folder_name);
+        if mode == RestoreMode::Full && filter.keeps_everything() {
+            fs::remove_dir_all(&self.m_noita_save_path)
+                .explain(&t!("fail_remove_crr_noita_save"))?;
+            Self::copy_dir(&src, &self.m_noita_save_path, filter).explain(&t!("fail_load_save"))?;
+            return Ok(());
+        }
+
+        if !src.exists() {
+            return throw(&t!("source_path_not_exist", path = src.to_str().unwrap()));
+        }
+        fs::create_dir_all(&self.m_noita_save_path).explain(&t!("fail_load_save"))?;
+        let live = Manifest::build(&self.m_noita_save_path, false, filter)
+            .explain(&t!("fail_remove_crr_noita_save"))?;
+        for relative in live.paths().filter(|path| mode.includes(path)) {
+            let path = self.m_noita_save_path.join(relative);
+            fs::remove_file(&path).explain(&t!("fail_remove_crr_noita_save"))?;
+            // drop the folders this empties like a full restore would, non-empty ones fail
+            for parent in path
+                .ancestors()
+                .skip(1)
+                .take_while(|parent| *parent != self.m_noita_save_path)
+            {
+                if fs::remove_dir(parent).is_err() {
+                    break;
+                }
+            }
+        }
+        // the file sets of the restore modes are split by the entries directly under "save00"
+        for entry in fs::read_dir(&src).explain(&t!("fail_load_save"))? {
+            let entry = entry.explain(&t!("fail_load_save"))?;
+            if !mode.includes_entry(&entry.file_name().to_string_lossy()) {
+                continue;
+            }
+            let dst = self.m_noita_save_path.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                Self::copy_dir_below(&src, &entry.path(), &dst, filter)
+            } else if filter.keeps(&entry.file_name().to_string_lossy()) {
+                Self::copy_file(&entry.path(), &dst)
+            } else {
+                Ok(())
+            }
+            .explain(&t!("fail_load_save"))?;
+        }
         Ok(())
     }
 
+    // the files `load_save` would replace and remove, nothing is touched
+    pub fn restore_plan(
+        &self,
+        folder_name: &str,
+        mode: RestoreMode,
+        filter: &PathFilter,
+    ) -> NSResult<RestorePlan> {
+        let snapshot = Manifest::build(&self.get_save_path(folder_name), false, filter)
+            .explain(&t!("fail_scan_save_tree"))?;
+        let live = Manifest::build(&self.m_noita_save_path, false, filter)
+            .explain(&t!("fail_scan_live_save"))?;
+        Ok(RestorePlan {
+            m_mode: mode,
+            m_replaced: snapshot
+                .paths()
+                .filter(|path| mode.includes(path))
+                .map(str::to_string)
+                .collect(),
+            m_removed: live
+                .paths()
+                .filter(|path| mode.includes(path) && !snapshot.contains(path))
+                .map(str::to_string)
+                .collect(),
+        })
+    }
+
+    // false if Noita has no "save_shared" to snapshot
+    pub fn save_shared(&self, folder_name: &str) -> NSBoolResult {
+        let src = self.live_shared_path();
+        let dst = self.shared_path(folder_name);
+        if dst.exists() {
+            fs::remove_dir_all(&dst).explain(&t!("fail_save_shared_settings"))?;
+        }
+        if !src.exists() {
+            return Ok(false);
+        }
+        Self::copy_dir(&src, &dst, &PathFilter::default())
+            .explain(&t!("fail_save_shared_settings"))?;
+        Ok(true)
+    }
+
+    pub fn load_shared(&self, folder_name: &str) -> NSComResult {
+        let src = self.shared_path(folder_name);
+        if !src.exists() {
+            return throw(&t!("no_shared_settings_in_save", save_name = folder_name));
+        }
+        let dst = self.live_shared_path();
+        if dst.exists() {
+            fs::remove_dir_all(&dst).explain(&t!("fail_load_shared_settings"))?;
+        }
+        Self::copy_dir(&src, &dst, &PathFilter::default())
+            .explain(&t!("fail_load_shared_settings"))?;
+        Ok(())
+    }
+
+    pub fn read_mod_config(&self) -> NSResult<String> {
+        let path = self.m_noita_save_path.join(MOD_CONFIG_FILE);
+        if !path.exists() {
+            return throw(&t!("no_mod_config"));
+        }
+        fs::read_to_string(path).explain(&t!("fail_read_mod_config"))
+    }
+
+    // copies the live save's mod settings into the profile, false if the save has none
+    pub fn save_mod_settings(&self, profile_name: &str) -> NSBoolResult {
+        let src = self.m_noita_save_path.join(MOD_SETTINGS_FILE);
+        let dst = self.mod_settings_path(profile_name);
+        if !src.exists() {
+            if dst.exists() {
+                fs::remove_file(dst).explain(&t!("fail_save_mod_profile"))?;
+            }
+            return Ok(false);
+        }
+        fs::create_dir_all(self.m_save_folder_path.join(MOD_PROFILE_FOLDER))
+            .explain(&t!("fail_save_mod_profile"))?;
+        fs::copy(src, dst).explain(&t!("fail_save_mod_profile"))?;
+        Ok(true)
+    }
+
+    pub fn remove_mod_settings(&self, profile_name: &str) -> NSComResult {
+        let path = self.mod_settings_path(profile_name);
+        if path.exists() {
+            fs::remove_file(path).explain(&t!("fail_remove_mod_profile"))?;
+        }
+        Ok(())
+    }
+
+    // copies the live mod config and settings into a new folder named after `stamp`
+    pub fn backup_mod_config(&self, stamp: &str) -> NSResult<PathBuf> {
+        let backup_dir = Self::unused_path(
+            self.m_save_folder_path
+                .join(MOD_CONFIG_BACKUP_FOLDER)
+                .join(stamp),
+        );
+        fs::create_dir_all(&backup_dir).explain(&t!("fail_backup_mod_config"))?;
+        for file in [MOD_CONFIG_FILE, MOD_SETTINGS_FILE] {
+            let src = self.m_noita_save_path.join(file);
+            if src.exists() {
+                fs::copy(src, backup_dir.join(file)).explain(&t!("fail_backup_mod_config"))?;
+            }
+        }
+        Ok(backup_dir)
+    }
+
+    // written through temporary files so Noita never reads a half written config,
+    // the profile's mod settings replace the live ones if given
+    pub fn write_mod_config(&self, mod_config: &str, profile_name: Option<&str>) -> NSComResult {
+        let replace = |dst: &Path, write: &dyn Fn(&Path) -> std::io::Result<()>| {
+            let tmp = dst.with_extension("tmp");
+            write(&tmp).explain(&t!("fail_write_mod_config"))?;
+            fs::rename(&tmp, dst).explain(&t!("fail_write_mod_config"))
+        };
+        if let Some(profile_name) = profile_name {
+            let src = self.mod_settings_path(profile_name);
+            replace(&self.m_noita_save_path.join(MOD_SETTINGS_FILE), &|tmp| {
+                fs::copy(&src, tmp).map(|_| ())
+            })?;
+        }
+        replace(&self.m_noita_save_path.join(MOD_CONFIG_FILE), &|tmp| {
+            fs::write(tmp, mod_config)
+        })
+    }
+
+    #[inline]
+    pub fn get_live_progress_path(&self) -> PathBuf {
+        self.m_noita_save_path.join(PROGRESS_FOLDER)
+    }
+
+    #[inline]
+    pub fn get_progress_profile_path(&self, profile_name: &str) -> PathBuf {
+        self.m_save_folder_path
+            .join(PROGRESS_PROFILE_FOLDER)
+            .join(profile_name)
+    }
+
+    // copied next to the old profile first, so a failed copy never loses the old one,
+    // profile names never start with "." so the staging folder can't be another profile
+    pub fn save_progress_profile(&self, profile_name: &str) -> NSComResult {
+        let dst = self.get_progress_profile_path(profile_name);
+        let staged = dst.with_file_name(format!(".{profile_name}.new"));
+        if staged.exists() {
+            fs::remove_dir_all(&staged).explain(&t!("fail_save_progress_profile"))?;
+        }
+        let live = self.get_live_progress_path();
+        if live.exists() {
+            Self::copy_dir(&live, &staged, &PathFilter::default())
+                .explain(&t!("fail_save_progress_profile"))?;
+        } else {
+            // a fresh account simply has no progress yet
+            fs::create_dir_all(&staged).explain(&t!("fail_save_progress_profile"))?;
+        }
+        if dst.exists() {
+            fs::remove_dir_all(&dst).explain(&t!("fail_save_progress_profile"))?;
+        }
+        fs::rename(&staged, &dst).explain(&t!("fail_save_progress_profile"))?;
+        Ok(())
+    }
+
+    pub fn remove_progress_profile(&self, profile_name: &str) -> NSComResult {
+        let path = self.get_progress_profile_path(profile_name);
+        if path.exists() {
+            fs::remove_dir_all(path).explain(&t!("fail_remove_progress_profile"))?;
+        }
+        Ok(())
+    }
+
+    // beside "save00" instead of inside it so a leftover never ends up in a snapshot, and not in
+    // the library since the swap has to stay a rename on the same drive
+    fn progress_staging_path(&self, suffix: &str) -> PathBuf {
+        let save_name = self
+            .m_noita_save_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        self.m_noita_save_path
+            .with_file_name(format!(".{save_name}_{PROGRESS_FOLDER}.{suffix}"))
+    }
+
+    // backs up the live progress into a folder named after `stamp`, then swaps the profile in
+    // with renames so that "persistent" is never left half copied, returns the backup folder
+    pub fn switch_progress(&self, profile_name: &str, stamp: &str) -> NSResult<PathBuf> {
+        let profile = self.get_progress_profile_path(profile_name);
+        if !profile.exists() {
+            return throw(&t!(
+                "source_path_not_exist",
+                path = profile.to_str().unwrap()
+            ));
+        }
+        let live = self.get_live_progress_path();
+        let backup_dir = Self::unused_path(
+            self.m_save_folder_path
+                .join(PROGRESS_BACKUP_FOLDER)
+                .join(stamp),
+        );
+        if live.exists() {
+            Self::copy_dir(&live, &backup_dir, &PathFilter::default())
+                .explain(&t!("fail_backup_progress"))?;
+        }
+
+        let staged = self.progress_staging_path("new");
+        let retired = self.progress_staging_path("old");
+        // older versions staged inside "save00" itself
+        for leftover in [
+            &staged,
+            &retired,
+            &live.with_extension("new"),
+            &live.with_extension("old"),
+        ] {
+            if leftover.exists() {
+                fs::remove_dir_all(leftover).explain(&t!("fail_switch_progress"))?;
+            }
+        }
+        Self::copy_dir(&profile, &staged, &PathFilter::default())
+            .explain(&t!("fail_switch_progress"))?;
+        if live.exists() {
+            fs::rename(&live, &retired).explain(&t!("fail_switch_progress"))?;
+        }
+        if let Err(e) = fs::rename(&staged, &live) {
+            if retired.exists() {
+                fs::rename(&retired, &live).explain(&t!("fail_switch_progress"))?;
+            }
+            return Err(e).explain(&t!("fail_switch_progress"));
+        }
+        if retired.exists() {
+            fs::remove_dir_all(&retired).explain(&t!("fail_switch_progress"))?;
+        }
+        Ok(backup_dir)
+    }
+
     pub fn rename_save(&self, old_name: &str, new_name: &str) -> NSComResult {
         fs::rename(
             self.m_save_folder_path.join(old_name),
             self.m_save_folder_path.join(new_name),
         )
         .explain(&t!("fail_rename_save_folder"))?;
+        for (old_path, new_path) in self
+            .side_files(old_name)
+            .into_iter()
+            .zip(self.side_files(new_name))
+        {
+            if old_path.exists() {
+                fs::rename(old_path, new_path).explain(&t!("fail_rename_save_folder"))?;
+            }
+        }
         Ok(())
     }
 
@@ -159,6 +661,11 @@ impl FileOperator {
         FileOperator::caculate_usage(&self.m_save_folder_path)
     }
 
+    #[inline]
+    pub fn get_save_usage(&self, folder_name: &str) -> NSResult<f64> {
+        FileOperator::caculate_usage(&self.m_save_folder_path.join(folder_name))
+    }
+
     fn caculate_usage(path: &Path) -> NSResult<f64> {
         let mut size = 0f64;
         for entry in
@@ -177,3 +684,230 @@ impl FileOperator {
         Ok(size)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+    use tempfile::TempDir;
+
+    // a live "save00" and a library next to it, both inside a temporary folder
+    fn operator() -> (TempDir, FileOperator) {
+        let dir = tempfile::tempdir().unwrap();
+        let live = dir.path().join("save00");
+        fs::create_dir_all(&live).unwrap();
+        let operator = FileOperator::with_save_folder(live, dir.path().join("library")).unwrap();
+        (dir, operator)
+    }
+
+    fn write(root: &Path, relative: &str, content: &str) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn set_mtime(root: &Path, relative: &str, mtime: SystemTime) {
+        fs::File::options()
+            .write(true)
+            .open(root.join(relative))
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+    }
+
+    #[test]
+    fn diff_files_against_the_live_save() {
+        let (_dir, operator) = operator();
+        let live = operator.get_live_save_path().to_path_buf();
+        write(&live, "world_state.xml", "<Entity/>");
+        write(&live, "world/area.bin", "area");
+        write(&live, "stats/old.xml", "old");
+        operator
+            .save("first", false, &PathFilter::default())
+            .unwrap();
+        assert!(
+            operator
+                .live_matches_snapshot("first", &PathFilter::default())
+                .unwrap()
+        );
+
+        write(&live, "world/area.bin", "bigger area");
+        write(&live, "world/new.bin", "new");
+        fs::remove_file(live.join("stats/old.xml")).unwrap();
+        let diff = operator
+            .diff_files(Some("first"), None, &PathFilter::default())
+            .unwrap();
+        assert_eq!(diff.m_added, ["world/new.bin"]);
+        assert_eq!(diff.m_removed, ["stats/old.xml"]);
+        assert_eq!(diff.m_modified, ["world/area.bin"]);
+        assert!(
+            !operator
+                .live_matches_snapshot("first", &PathFilter::default())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn diff_files_between_snapshots_uses_recorded_mtimes() {
+        let (_dir, operator) = operator();
+        let live = operator.get_live_save_path().to_path_buf();
+        write(&live, "player.xml", "<Entity/>");
+        let earlier = SystemTime::now() - Duration::from_secs(3600);
+        set_mtime(&live, "player.xml", earlier);
+        operator.save("a", false, &PathFilter::default()).unwrap();
+        // same content and size, only rewritten by the game
+        set_mtime(&live, "player.xml", SystemTime::now());
+        operator.save("b", false, &PathFilter::default()).unwrap();
+
+        let diff = operator
+            .diff_files(Some("a"), Some("b"), &PathFilter::default())
+            .unwrap();
+        assert_eq!(diff.m_modified, ["player.xml"]);
+        assert!(diff.m_added.is_empty() && diff.m_removed.is_empty());
+    }
+
+    fn read(root: &Path, relative: &str) -> Option<String> {
+        fs::read_to_string(root.join(relative)).ok()
+    }
+
+    // a snapshot "snap" of a live save that then moves on, in both the run and the progress
+    fn snapshot_then_play(operator: &FileOperator, filter: &PathFilter) -> PathBuf {
+        let live = operator.get_live_save_path().to_path_buf();
+        write(&live, "player.xml", "snap");
+        write(&live, "world/area.bin", "snap");
+        write(&live, "persistent/flags/card", "snap");
+        operator.save("snap", false, filter).unwrap();
+
+        write(&live, "player.xml", "played");
+        write(&live, "world/new.bin", "played");
+        write(&live, "persistent/flags/card", "played");
+        write(&live, "persistent/flags/new", "played");
+        live
+    }
+
+    #[test]
+    fn run_only_restore_keeps_persistent() {
+        let (_dir, operator) = operator();
+        let filter = PathFilter::default();
+        let live = snapshot_then_play(&operator, &filter);
+
+        let plan = operator
+            .restore_plan("snap", RestoreMode::RunOnly, &filter)
+            .unwrap();
+        assert_eq!(plan.m_replaced, ["player.xml", "world/area.bin"]);
+        assert_eq!(plan.m_removed, ["world/new.bin"]);
+
+        operator
+            .load_save("snap", RestoreMode::RunOnly, &filter)
+            .unwrap();
+        assert_eq!(read(&live, "player.xml").as_deref(), Some("snap"));
+        assert_eq!(read(&live, "world/area.bin").as_deref(), Some("snap"));
+        assert!(!live.join("world/new.bin").exists());
+        assert_eq!(
+            read(&live, "persistent/flags/card").as_deref(),
+            Some("played")
+        );
+        assert_eq!(
+            read(&live, "persistent/flags/new").as_deref(),
+            Some("played")
+        );
+    }
+
+    #[test]
+    fn progress_only_restore_keeps_the_run() {
+        let (_dir, operator) = operator();
+        let filter = PathFilter::default();
+        let live = snapshot_then_play(&operator, &filter);
+
+        let plan = operator
+            .restore_plan("snap", RestoreMode::ProgressOnly, &filter)
+            .unwrap();
+        assert_eq!(plan.m_replaced, ["persistent/flags/card"]);
+        assert_eq!(plan.m_removed, ["persistent/flags/new"]);
+
+        operator
+            .load_save("snap", RestoreMode::ProgressOnly, &filter)
+            .unwrap();
+        assert_eq!(
+            read(&live, "persistent/flags/card").as_deref(),
+            Some("snap")
+        );
+        assert!(!live.join("persistent/flags/new").exists());
+        assert_eq!(read(&live, "player.xml").as_deref(), Some("played"));
+        assert_eq!(read(&live, "world/new.bin").as_deref(), Some("played"));
+    }
+
+    #[test]
+    fn filtered_restore_leaves_excluded_files_alone() {
+        let (_dir, operator) = operator();
+        let filter = crate::utils::snapshot_filter::SnapshotFilter {
+            include: Vec::new(),
+            exclude: vec!["stats".to_string()],
+        }
+        .compile()
+        .unwrap();
+        let live = operator.get_live_save_path().to_path_buf();
+        write(&live, "stats/sessions/old_stats.xml", "old");
+        snapshot_then_play(&operator, &filter);
+        write(&live, "stats/sessions/new_stats.xml", "new");
+
+        let plan = operator
+            .restore_plan("snap", RestoreMode::Full, &filter)
+            .unwrap();
+        assert!(
+            plan.m_replaced
+                .iter()
+                .all(|path| !path.starts_with("stats"))
+        );
+        assert!(plan.m_removed.iter().all(|path| !path.starts_with("stats")));
+
+        operator
+            .load_save("snap", RestoreMode::Full, &filter)
+            .unwrap();
+        assert_eq!(read(&live, "player.xml").as_deref(), Some("snap"));
+        assert!(!live.join("world/new.bin").exists());
+        assert!(!live.join("persistent/flags/new").exists());
+        assert_eq!(
+            read(&live, "stats/sessions/old_stats.xml").as_deref(),
+            Some("old")
+        );
+        assert_eq!(
+            read(&live, "stats/sessions/new_stats.xml").as_deref(),
+            Some("new")
+        );
+    }
+
+    #[test]
+    fn switch_progress_stages_outside_the_live_save() {
+        let (dir, operator) = operator();
+        let live = operator.get_live_save_path().to_path_buf();
+        write(&live, "persistent/flags/profile_card", "profile");
+        operator.save_progress_profile("profile").unwrap();
+        fs::remove_dir_all(live.join("persistent")).unwrap();
+        write(&live, "persistent/flags/live_card", "live");
+        write(&live, "player.xml", "run");
+
+        let backup = operator.switch_progress("profile", "stamp").unwrap();
+        assert_eq!(
+            read(&live, "persistent/flags/profile_card").as_deref(),
+            Some("profile")
+        );
+        assert!(!live.join("persistent/flags/live_card").exists());
+        assert_eq!(read(&backup, "flags/live_card").as_deref(), Some("live"));
+        assert_eq!(read(&live, "player.xml").as_deref(), Some("run"));
+
+        // nothing but the live save itself is left inside or beside it
+        let mut in_live: Vec<_> = fs::read_dir(&live)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        in_live.sort();
+        assert_eq!(in_live, ["persistent", "player.xml"]);
+        let mut beside_live: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        beside_live.sort();
+        assert_eq!(beside_live, ["library", "save00"]);
+    }
+}
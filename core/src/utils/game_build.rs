@@ -0,0 +1,19 @@
+use std::fs;
+use std::path::Path;
+
+use super::error::*;
+
+// written next to "noita.exe" by every Noita build, the beta branch included
+const VERSION_HASH_FILE: &str = "_version_hash.txt";
+
+// None if the install directory holds no version marker, e.g. an unusual or very old install
+pub fn read_game_build(noita_dir: &Path) -> NSResult<Option<String>> {
+    let path = noita_dir.join(VERSION_HASH_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let build = fs::read_to_string(&path)
+        .explain(&t!("fail_read_game_build", path = format!("{path:?}")))?;
+    let build = build.trim();
+    Ok((!build.is_empty()).then(|| build.to_string()))
+}
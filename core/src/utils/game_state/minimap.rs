@@ -0,0 +1,336 @@
+use std::fs;
+use std::io::BufWriter;
+use std::path::Path;
+
+use crate::utils::error::*;
+
+// every chunk covers 512x512 cells
+const CHUNK_SIZE: i64 = 512;
+// cells per preview pixel, raised when the explored area would exceed MAX_PREVIEW_SIDE
+const MIN_CELLS_PER_PIXEL: i64 = 8;
+const MAX_PREVIEW_SIDE: i64 = 1024;
+// the header and a custom color for every cell, only caps what is reserved up front since
+// the decompressed size comes straight from the file
+const MAX_CHUNK_DATA: usize = 12 + 5 * (CHUNK_SIZE * CHUNK_SIZE) as usize;
+
+const BACKGROUND: [u8; 4] = [0, 0, 0, 0];
+const UNEXPLORED: [u8; 4] = [24, 24, 28, 255];
+const PLAYER_MARKER: [u8; 4] = [255, 40, 40, 255];
+
+// materials that are hard to tell apart when colored by hash
+const KNOWN_MATERIALS: [(&str, [u8; 4]); 10] = [
+    ("air", BACKGROUND),
+    ("water", [46, 102, 200, 255]),
+    ("water_static", [46, 102, 200, 255]),
+    ("blood", [140, 10, 10, 255]),
+    ("lava", [255, 110, 20, 255]),
+    ("gold", [240, 200, 40, 255]),
+    ("acid", [90, 220, 40, 255]),
+    ("oil", [40, 34, 30, 255]),
+    ("toxicsludge", [150, 210, 50, 255]),
+    ("magic_liquid_teleportation", [100, 190, 255, 255]),
+];
+
+// the cells of one chunk, read from "world/world_<x>_<y>.png_petri"
+struct Chunk {
+    m_x: i64,
+    m_y: i64,
+    m_width: usize,
+    m_height: usize,
+    // one RGBA color per cell
+    m_pixels: Vec<[u8; 4]>,
+}
+
+// FastLZ as used by Noita, level 1 and 2, None if the data is corrupt or grows past `expected_len`
+fn fastlz_decompress(input: &[u8], expected_len: usize) -> Option<Vec<u8>> {
+    let level = (*input.first()? >> 5) + 1;
+    let mut output: Vec<u8> = Vec::with_capacity(expected_len.min(MAX_CHUNK_DATA));
+    let mut ip = 0;
+    let mut ctrl = (input[ip] & 31) as usize;
+    ip += 1;
+    loop {
+        if ctrl >= 32 {
+            let mut len = (ctrl >> 5) - 1;
+            let mut ofs = (ctrl & 31) << 8;
+            if level == 1 {
+                if len == 6 {
+                    len += *input.get(ip)? as usize;
+                    ip += 1;
+                }
+            } else if len == 6 {
+                loop {
+                    let code = *input.get(ip)?;
+                    ip += 1;
+                    len += code as usize;
+                    if code != 255 {
+                        break;
+                    }
+                }
+            }
+            let code = *input.get(ip)? as usize;
+            ip += 1;
+            let mut distance = ofs + code + 1;
+            if level == 2 && code == 255 && ofs == 31 << 8 {
+                ofs = (*input.get(ip)? as usize) << 8 | *input.get(ip + 1)? as usize;
+                ip += 2;
+                distance = ofs + 8191 + 1;
+            }
+            let start = output.len().checked_sub(distance)?;
+            if output.len() + len + 3 > expected_len {
+                return None;
+            }
+            // the match may overlap the bytes it produces
+            for i in 0..len + 3 {
+                output.push(output[start + i]);
+            }
+        } else {
+            let literal = input.get(ip..ip + ctrl + 1)?;
+            if output.len() + literal.len() > expected_len {
+                return None;
+            }
+            output.extend_from_slice(literal);
+            ip += ctrl + 1;
+        }
+        if ip >= input.len() {
+            break;
+        }
+        ctrl = input[ip] as usize;
+        ip += 1;
+    }
+    Some(output)
+}
+
+struct Reader<'a> {
+    m_data: &'a [u8],
+    m_pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.m_data.get(self.m_pos..self.m_pos + len)?;
+        self.m_pos += len;
+        Some(bytes)
+    }
+
+    fn u32_be(&mut self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.bytes(4)?.try_into().ok()?))
+    }
+}
+
+fn material_color(name: &str) -> [u8; 4] {
+    if let Some((_, color)) = KNOWN_MATERIALS.iter().find(|(known, _)| *known == name) {
+        return *color;
+    }
+    // FNV-1a, so that a material keeps its color across previews
+    let hash = name.bytes().fold(0x811c9dc5u32, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x01000193)
+    });
+    let channel = |shift: u32| 64 + ((hash >> shift) & 0xff) as u8 / 2;
+    [channel(0), channel(8), channel(16), 255]
+}
+
+impl Chunk {
+    // None if the file isn't a chunk or can't be decoded
+    fn read(path: &Path) -> NSResult<Option<Self>> {
+        let Some((x, y)) = Self::position_of(path) else {
+            return Ok(None);
+        };
+        let raw = fs::read(path).explain(&t!("fail_read_game_file", path = format!("{path:?}")))?;
+        Ok(Self::decode(&raw).map(|(width, height, pixels)| Self {
+            m_x: x,
+            m_y: y,
+            m_width: width,
+            m_height: height,
+            m_pixels: pixels,
+        }))
+    }
+
+    // "world_-512_1024.png_petri" -> (-512, 1024)
+    fn position_of(path: &Path) -> Option<(i64, i64)> {
+        let name = path.file_name()?.to_str()?;
+        let (x, y) = name
+            .strip_prefix("world_")?
+            .strip_suffix(".png_petri")?
+            .split_once('_')?;
+        Some((x.parse().ok()?, y.parse().ok()?))
+    }
+
+    // a u32 compressed size and a u32 decompressed size, both little endian, then the data
+    fn decode(raw: &[u8]) -> Option<(usize, usize, Vec<[u8; 4]>)> {
+        let compressed_len = u32::from_le_bytes(raw.get(0..4)?.try_into().ok()?) as usize;
+        let decompressed_len = u32::from_le_bytes(raw.get(4..8)?.try_into().ok()?) as usize;
+        let body = raw.get(8..8 + compressed_len)?;
+        let data = if compressed_len == decompressed_len {
+            body.to_vec()
+        } else {
+            fastlz_decompress(body, decompressed_len)?
+        };
+
+        // version, width, height, one byte per cell, the material names used by the cells
+        // and the colors of the cells with a custom color, all big endian
+        let mut reader = Reader {
+            m_data: &data,
+            m_pos: 0,
+        };
+        let _version = reader.u32_be()?;
+        let width = reader.u32_be()? as usize;
+        let height = reader.u32_be()? as usize;
+        let cells = reader.bytes(width * height)?;
+        let materials = (0..reader.u32_be()?)
+            .map(|_| {
+                let len = reader.u32_be()? as usize;
+                Some(material_color(&String::from_utf8_lossy(reader.bytes(len)?)))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let custom_colors = (0..reader.u32_be()?)
+            .map(|_| {
+                let [a, r, g, b] = reader.u32_be()?.to_be_bytes();
+                Some([r, g, b, a])
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let mut custom_colors = custom_colors.into_iter();
+        let pixels = cells
+            .iter()
+            .map(|&cell| {
+                // the high bit marks a cell with its own color, e.g. a painted or stained one
+                if cell & 0x80 != 0 {
+                    custom_colors.next().unwrap_or(UNEXPLORED)
+                } else {
+                    materials
+                        .get((cell & 0x7f) as usize)
+                        .copied()
+                        .unwrap_or(UNEXPLORED)
+                }
+            })
+            .collect();
+        Some((width, height, pixels))
+    }
+}
+
+// renders a downscaled overview of the explored chunks in `save_dir` into `output`,
+// returns false without writing anything if the save has no chunk
+pub fn render_minimap(save_dir: &Path, player: Option<(f64, f64)>, output: &Path) -> NSBoolResult {
+    let world_dir = save_dir.join("world");
+    if !world_dir.exists() {
+        return Ok(false);
+    }
+    let paths: Vec<_> = fs::read_dir(&world_dir)
+        .explain(&t!("fail_read_game_file", path = format!("{world_dir:?}")))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| Chunk::position_of(path).is_some())
+        .collect();
+    if paths.is_empty() {
+        return Ok(false);
+    }
+
+    let positions: Vec<(i64, i64)> = paths
+        .iter()
+        .filter_map(|path| Chunk::position_of(path))
+        .collect();
+    let min_x = positions.iter().map(|(x, _)| *x).min().unwrap_or(0);
+    let min_y = positions.iter().map(|(_, y)| *y).min().unwrap_or(0);
+    let max_x = positions.iter().map(|(x, _)| *x).max().unwrap_or(0) + CHUNK_SIZE;
+    let max_y = positions.iter().map(|(_, y)| *y).max().unwrap_or(0) + CHUNK_SIZE;
+    let span = (max_x - min_x).max(max_y - min_y);
+    let scale = MIN_CELLS_PER_PIXEL.max((span + MAX_PREVIEW_SIDE - 1) / MAX_PREVIEW_SIDE);
+    let width = ((max_x - min_x) / scale).max(1) as usize;
+    let height = ((max_y - min_y) / scale).max(1) as usize;
+
+    let mut image = vec![UNEXPLORED; width * height];
+    // chunks are decoded one at a time to keep memory flat on big worlds
+    for path in &paths {
+        let Some(chunk) = Chunk::read(path)? else {
+            continue;
+        };
+        for py in 0..(chunk.m_height as i64 / scale) {
+            for px in 0..(chunk.m_width as i64 / scale) {
+                let ix = (chunk.m_x - min_x) / scale + px;
+                let iy = (chunk.m_y - min_y) / scale + py;
+                if ix < 0 || iy < 0 || ix >= width as i64 || iy >= height as i64 {
+                    continue;
+                }
+                let cell = (py * scale) as usize * chunk.m_width + (px * scale) as usize;
+                image[iy as usize * width + ix as usize] = chunk.m_pixels[cell];
+            }
+        }
+    }
+
+    if let Some((x, y)) = player {
+        let (cx, cy) = (
+            ((x as i64 - min_x) / scale) as isize,
+            ((y as i64 - min_y) / scale) as isize,
+        );
+        for d in -3isize..=3 {
+            for (mx, my) in [(cx + d, cy), (cx, cy + d)] {
+                if mx >= 0 && my >= 0 && (mx as usize) < width && (my as usize) < height {
+                    image[my as usize * width + mx as usize] = PLAYER_MARKER;
+                }
+            }
+        }
+    }
+
+    write_png(output, width as u32, height as u32, &image)?;
+    Ok(true)
+}
+
+fn write_png(path: &Path, width: u32, height: u32, image: &[[u8; 4]]) -> NSComResult {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).explain(&t!("fail_write_minimap"))?;
+    }
+    let file = fs::File::create(path).explain(&t!("fail_write_minimap"))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().explain(&t!("fail_write_minimap"))?;
+    writer
+        .write_image_data(image.as_flattened())
+        .explain(&t!("fail_write_minimap"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fastlz_decompress;
+
+    #[test]
+    fn fastlz_literal_and_overlapping_match() {
+        // "a" as a literal, then 4 bytes copied from 1 byte back
+        assert_eq!(
+            fastlz_decompress(&[0x00, b'a', 0x40, 0x00], 5).as_deref(),
+            Some(&b"aaaaa"[..])
+        );
+    }
+
+    #[test]
+    fn fastlz_long_match() {
+        // "abc" as a literal, then a match of 6 + 0 + 3 bytes from 3 bytes back
+        assert_eq!(
+            fastlz_decompress(&[0x02, b'a', b'b', b'c', 0xe0, 0x00, 0x02], 12).as_deref(),
+            Some(&b"abcabcabcabc"[..])
+        );
+    }
+
+    #[test]
+    fn fastlz_level_2_long_match() {
+        // the length of a level 2 match continues while its bytes are 255
+        let mut input = vec![0x20, b'x', 0xe0, 0xff, 0x01, 0x00];
+        let decoded = fastlz_decompress(&input, 1 + 6 + 255 + 1 + 3).unwrap();
+        assert_eq!(decoded.len(), 266);
+        assert!(decoded.iter().all(|&byte| byte == b'x'));
+
+        input.truncate(4);
+        assert_eq!(fastlz_decompress(&input, 266), None);
+    }
+
+    #[test]
+    fn fastlz_rejects_corrupt_data() {
+        // a match reaching before the start of the output
+        assert_eq!(fastlz_decompress(&[0x00, b'a', 0x40, 0x05], 5), None);
+        // a literal run longer than the input
+        assert_eq!(fastlz_decompress(&[0x04, b'a'], 5), None);
+        // more output than the header announced
+        assert_eq!(fastlz_decompress(&[0x00, b'a', 0x40, 0x00], 4), None);
+        assert_eq!(fastlz_decompress(&[], 4), None);
+    }
+}
@@ -0,0 +1,53 @@
+pub mod minimap;
+pub mod mods;
+pub mod perks;
+pub mod player;
+pub mod progress;
+pub mod stats;
+pub mod wands;
+pub mod world;
+
+use roxmltree::Node;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use super::error::*;
+
+// Noita counts durations such as cast delay and effect time in frames
+const FRAMES_PER_SECOND: f64 = 60.0;
+
+// Noita's entity files keep an entity's components as its direct children
+fn component<'a, 'input>(entity: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    entity.children().find(|node| node.has_tag_name(name))
+}
+
+// the entries of a WorldStateComponent's <flags>, stored either as an attribute or as text
+fn world_flags<'a>(world_state: Node<'a, '_>) -> impl Iterator<Item = &'a str> {
+    component(world_state, "flags")
+        .into_iter()
+        .flat_map(|flags| flags.children())
+        .filter(|node| node.is_element())
+        .filter_map(|node| {
+            node.attributes()
+                .map(|item| item.value())
+                .chain(node.text())
+                .map(str::trim)
+                .find(|value| !value.is_empty())
+        })
+}
+
+fn attr<T: FromStr>(node: Node, name: &str) -> Option<T> {
+    node.attribute(name)?.trim().parse().ok()
+}
+
+// None if the file doesn't exist, e.g. a save made in the main menu
+fn read_xml(path: &Path) -> NSResult<Option<String>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(fs::read_to_string(path).explain(&t!(
+        "fail_read_game_file",
+        path = format!("{path:?}")
+    ))?))
+}
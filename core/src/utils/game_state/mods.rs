@@ -0,0 +1,221 @@
+use roxmltree::{Document, Node};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::read_xml;
+use crate::utils::error::*;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct ModList {
+    // names of the enabled mods, in load order
+    pub m_mods: Vec<String>,
+}
+
+// how the mods enabled for a snapshot differ from the ones currently enabled
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ModMismatch {
+    // enabled in the snapshot only
+    pub m_missing: Vec<String>,
+    // currently enabled only
+    pub m_extra: Vec<String>,
+    // the mods enabled in both are loaded in a different order
+    pub m_reordered: bool,
+}
+
+impl ModList {
+    // None if the save has no "mod_config.xml", e.g. one that never got past the main menu
+    pub fn from_save(save_dir: &Path) -> NSResult<Option<Self>> {
+        let Some(text) = read_xml(&save_dir.join("mod_config.xml"))? else {
+            return Ok(None);
+        };
+        let doc = Document::parse(&text).explain(&t!("fail_parse_mod_config_xml"))?;
+        // <Mods> holds one <Mod enabled="0|1" name="..."/> per installed mod, in load order
+        Ok(Some(Self {
+            m_mods: doc
+                .root_element()
+                .children()
+                .filter(|node| node.has_tag_name("Mod"))
+                .filter(|node| {
+                    node.attribute("enabled")
+                        .is_some_and(|value| matches!(value.trim(), "1" | "true"))
+                })
+                .filter_map(|node| node.attribute("name"))
+                .map(|name| name.trim().to_string())
+                .collect(),
+        }))
+    }
+
+    #[inline]
+    pub fn has_mod(&self, name: &str) -> bool {
+        self.m_mods.iter().any(|item| item == name)
+    }
+
+    // None if both lists enable the same mods in the same order
+    pub fn mismatch(&self, current: &ModList) -> Option<ModMismatch> {
+        let missing: Vec<String> = self
+            .m_mods
+            .iter()
+            .filter(|name| !current.has_mod(name))
+            .cloned()
+            .collect();
+        let extra: Vec<String> = current
+            .m_mods
+            .iter()
+            .filter(|name| !self.has_mod(name))
+            .cloned()
+            .collect();
+        let reordered = !self
+            .m_mods
+            .iter()
+            .filter(|name| current.has_mod(name))
+            .eq(current.m_mods.iter().filter(|name| self.has_mod(name)));
+        if missing.is_empty() && extra.is_empty() && !reordered {
+            return None;
+        }
+        Some(ModMismatch {
+            m_missing: missing,
+            m_extra: extra,
+            m_reordered: reordered,
+        })
+    }
+
+    // `mod_config` with exactly these mods enabled, moved to the front in this order, returns
+    // the new text and the mods that aren't installed and thus were left out
+    pub fn apply_to_config(&self, mod_config: &str) -> NSResult<(String, Vec<String>)> {
+        let doc = Document::parse(mod_config).explain(&t!("fail_parse_mod_config_xml"))?;
+        let entries: Vec<Node> = doc
+            .root_element()
+            .children()
+            .filter(|node| node.has_tag_name("Mod"))
+            .collect();
+        fn name_of<'a>(node: &Node<'a, '_>) -> &'a str {
+            node.attribute("name").map(str::trim).unwrap_or_default()
+        }
+
+        let mut missing = Vec::new();
+        let mut ordered: Vec<(Node, bool)> = Vec::new();
+        for name in self.m_mods.iter() {
+            match entries.iter().find(|node| name_of(node) == name) {
+                Some(node) => ordered.push((*node, true)),
+                None => missing.push(name.clone()),
+            }
+        }
+        ordered.extend(
+            entries
+                .iter()
+                .filter(|node| !self.has_mod(name_of(node)))
+                .map(|node| (*node, false)),
+        );
+
+        let mut text = String::from("<Mods>\n");
+        for (node, enabled) in ordered {
+            text.push_str("  <Mod");
+            for attribute in node.attributes() {
+                let value = if attribute.name() == "enabled" {
+                    if enabled { "1" } else { "0" }
+                } else {
+                    attribute.value()
+                };
+                text += &format!(" {}=\"{}\"", attribute.name(), escape_xml(value));
+            }
+            text.push_str(" >\n  </Mod>\n");
+        }
+        text.push_str("</Mods>\n");
+        Ok((text, missing))
+    }
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl std::fmt::Display for ModList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.m_mods.is_empty() {
+            write!(f, "{}: {}", t!("mods_enabled"), t!("perk_none"))
+        } else {
+            write!(f, "{}: {}", t!("mods_enabled"), self.m_mods.join(", "))
+        }
+    }
+}
+
+impl std::fmt::Display for ModMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.m_missing.is_empty() {
+            write!(
+                f,
+                "\n\t{}: {}",
+                t!("mods_only_in_save"),
+                self.m_missing.join(", ")
+            )?;
+        }
+        if !self.m_extra.is_empty() {
+            write!(
+                f,
+                "\n\t{}: {}",
+                t!("mods_only_enabled_now"),
+                self.m_extra.join(", ")
+            )?;
+        }
+        if self.m_reordered {
+            write!(f, "\n\t{}", t!("mods_reordered"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn mods(names: &[&str]) -> ModList {
+        ModList {
+            m_mods: names.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn reads_only_enabled_mods_in_load_order() {
+        let save = tempfile::tempdir().unwrap();
+        assert!(ModList::from_save(save.path()).unwrap().is_none());
+
+        fs::write(
+            save.path().join("mod_config.xml"),
+            r#"<Mods>
+                <Mod enabled="1" name="grahamsperks" settings_fold_open="0" workshop_item_id="0"/>
+                <Mod enabled="0" name="nightmare" settings_fold_open="0" workshop_item_id="0"/>
+                <Mod enabled="true" name=" spell_lab "/>
+                <Mod name="no_enabled_attribute"/>
+            </Mods>"#,
+        )
+        .unwrap();
+        let list = ModList::from_save(save.path()).unwrap().unwrap();
+        assert_eq!(list, mods(&["grahamsperks", "spell_lab"]));
+    }
+
+    #[test]
+    fn mismatch_lists_missing_extra_and_reordered_mods() {
+        let saved = mods(&["a", "b", "c"]);
+        assert!(saved.mismatch(&mods(&["a", "b", "c"])).is_none());
+
+        // "c" was disabled since and "d" enabled, "a" and "b" keep their order
+        let mismatch = saved.mismatch(&mods(&["a", "d", "b"])).unwrap();
+        assert_eq!(mismatch.m_missing, ["c"]);
+        assert_eq!(mismatch.m_extra, ["d"]);
+        assert!(!mismatch.m_reordered);
+
+        let mismatch = saved.mismatch(&mods(&["c", "a", "b"])).unwrap();
+        assert!(mismatch.m_missing.is_empty() && mismatch.m_extra.is_empty());
+        assert!(mismatch.m_reordered);
+
+        // no mods installed at all now
+        let mismatch = saved.mismatch(&mods(&[])).unwrap();
+        assert_eq!(mismatch.m_missing, ["a", "b", "c"]);
+        assert!(!mismatch.m_reordered);
+    }
+}
@@ -0,0 +1,271 @@
+use roxmltree::{Document, Node};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::{FRAMES_PER_SECOND, attr, component, read_xml, world_flags};
+use crate::utils::error::*;
+
+// every picked perk leaves a "PERK_PICKED_<ID>" flag in "world_state.xml",
+// perks that can stack also keep a "PERK_PICKED_<ID>_PICKUP_COUNT" lua global
+const PERK_FLAG_PREFIX: &str = "PERK_PICKED_";
+const PERK_COUNT_SUFFIX: &str = "_PICKUP_COUNT";
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PickedPerk {
+    // e.g. "EDIT_WANDS_EVERYWHERE"
+    pub m_id: String,
+    pub m_count: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct GameEffect {
+    // e.g. "PROTECTION_FIRE", or the custom effect id for effects added by perks
+    pub m_id: String,
+    // remaining frames, -1 for permanent effects
+    pub m_frames: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PerkSummary {
+    pub m_perks: Vec<PickedPerk>,
+    pub m_effects: Vec<GameEffect>,
+}
+
+impl PerkSummary {
+    // None if the save has neither "world_state.xml" nor "player.xml"
+    pub fn from_save(save_dir: &Path) -> NSResult<Option<Self>> {
+        let world_state = read_xml(&save_dir.join("world_state.xml"))?;
+        let player = read_xml(&save_dir.join("player.xml"))?;
+        if world_state.is_none() && player.is_none() {
+            return Ok(None);
+        }
+
+        let mut summary = Self::default();
+        if let Some(text) = world_state {
+            let doc = Document::parse(&text).explain(&t!("fail_parse_world_state_xml"))?;
+            summary.m_perks = Self::read_perks(doc.root_element());
+        }
+        if let Some(text) = player {
+            let doc = Document::parse(&text).explain(&t!("fail_parse_player_xml"))?;
+            summary.m_effects = Self::read_effects(doc.root_element());
+        }
+        Ok(Some(summary))
+    }
+
+    fn read_perks(world_state: Node) -> Vec<PickedPerk> {
+        let mut perks: BTreeMap<String, u32> = BTreeMap::new();
+        let Some(world_state) = component(world_state, "WorldStateComponent") else {
+            return Vec::new();
+        };
+        for id in world_flags(world_state).filter_map(|flag| flag.strip_prefix(PERK_FLAG_PREFIX)) {
+            if !id.ends_with(PERK_COUNT_SUFFIX) {
+                perks.entry(id.to_string()).or_insert(1);
+            }
+        }
+        // lua globals are stored as <E key="..." value="..."/>
+        let globals = component(world_state, "lua_globals")
+            .into_iter()
+            .flat_map(|globals| globals.children())
+            .filter(|node| node.is_element());
+        for node in globals {
+            if let Some(id) = node
+                .attribute("key")
+                .and_then(|key| key.strip_prefix(PERK_FLAG_PREFIX))
+                .and_then(|key| key.strip_suffix(PERK_COUNT_SUFFIX))
+            {
+                let count = attr(node, "value").unwrap_or(1);
+                let entry = perks.entry(id.to_string()).or_insert(0);
+                *entry = (*entry).max(count);
+            }
+        }
+        perks
+            .into_iter()
+            .map(|(id, count)| PickedPerk {
+                m_id: id,
+                m_count: count.max(1),
+            })
+            .collect()
+    }
+
+    // effects live on child entities of the player, each with a GameEffectComponent
+    fn read_effects(player: Node) -> Vec<GameEffect> {
+        let mut effects: Vec<GameEffect> = player
+            .children()
+            .filter(|node| node.has_tag_name("Entity"))
+            .flat_map(|entity| entity.children())
+            .filter(|node| node.has_tag_name("GameEffectComponent"))
+            .filter_map(|component| {
+                let id = component
+                    .attribute("custom_effect_id")
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .or_else(|| component.attribute("effect").map(str::trim))
+                    .filter(|id| !id.is_empty() && *id != "NONE")?;
+                Some(GameEffect {
+                    m_id: id.to_string(),
+                    m_frames: attr(component, "frames").unwrap_or(-1),
+                })
+            })
+            .collect();
+        effects.sort_by(|a, b| a.m_id.cmp(&b.m_id));
+        effects
+    }
+
+    // accepts the perk id with or without the "PERK_PICKED_" prefix, ignoring case
+    pub fn has_perk(&self, perk: &str) -> bool {
+        let perk = perk.trim().to_uppercase();
+        let perk = perk.strip_prefix(PERK_FLAG_PREFIX).unwrap_or(&perk);
+        self.m_perks.iter().any(|item| item.m_id == perk)
+    }
+}
+
+// "{}" lists the picked perks, "{:#}" additionally lists the active effects
+impl std::fmt::Display for PerkSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let perks = self
+            .m_perks
+            .iter()
+            .map(|perk| {
+                if perk.m_count > 1 {
+                    format!("{} x{}", perk.m_id, perk.m_count)
+                } else {
+                    perk.m_id.clone()
+                }
+            })
+            .collect::<Vec<_>>();
+        write!(
+            f,
+            "{}: {}",
+            t!("perk_perks"),
+            if perks.is_empty() {
+                t!("perk_none").to_string()
+            } else {
+                perks.join(", ")
+            }
+        )?;
+        if f.alternate() {
+            let effects = self
+                .m_effects
+                .iter()
+                .map(|effect| {
+                    if effect.m_frames < 0 {
+                        effect.m_id.clone()
+                    } else {
+                        format!(
+                            "{} ({:.0}s)",
+                            effect.m_id,
+                            effect.m_frames as f64 / FRAMES_PER_SECOND
+                        )
+                    }
+                })
+                .collect::<Vec<_>>();
+            write!(
+                f,
+                "\n\t{}: {}",
+                t!("perk_effects"),
+                if effects.is_empty() {
+                    t!("perk_none").to_string()
+                } else {
+                    effects.join(", ")
+                }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn save_with(files: &[(&str, &str)]) -> TempDir {
+        let save = tempfile::tempdir().unwrap();
+        for (name, xml) in files {
+            fs::write(save.path().join(name), xml).unwrap();
+        }
+        save
+    }
+
+    fn perk(id: &str, count: u32) -> PickedPerk {
+        PickedPerk {
+            m_id: id.to_string(),
+            m_count: count,
+        }
+    }
+
+    #[test]
+    fn reads_perks_and_effects() {
+        let save = save_with(&[
+            (
+                "world_state.xml",
+                r#"<Entity>
+                    <WorldStateComponent day_count="1">
+                        <flags>
+                            <string contents="PERK_PICKED_EDIT_WANDS_EVERYWHERE"/>
+                            <string>PERK_PICKED_EXTRA_HP</string>
+                            <string contents="PERK_PICKED_EXTRA_HP_PICKUP_COUNT"/>
+                            <string contents="boss_centipede"/>
+                        </flags>
+                        <lua_globals>
+                            <E key="PERK_PICKED_EXTRA_HP_PICKUP_COUNT" value="3"/>
+                            <E key="PERK_PICKED_PROTECTION_FIRE_PICKUP_COUNT" value="0"/>
+                            <E key="SOMETHING_ELSE" value="7"/>
+                        </lua_globals>
+                    </WorldStateComponent>
+                </Entity>"#,
+            ),
+            (
+                "player.xml",
+                r#"<Entity tags="player_unit">
+                    <Entity><GameEffectComponent effect="PROTECTION_FIRE" frames="-1"/></Entity>
+                    <Entity><GameEffectComponent effect="CUSTOM" custom_effect_id="BERSERK"
+                                                 frames="600"/></Entity>
+                    <Entity><GameEffectComponent effect="NONE"/></Entity>
+                    <GameEffectComponent effect="NOT_ON_A_CHILD"/>
+                </Entity>"#,
+            ),
+        ]);
+        let summary = PerkSummary::from_save(save.path()).unwrap().unwrap();
+        assert_eq!(
+            summary.m_perks,
+            [
+                perk("EDIT_WANDS_EVERYWHERE", 1),
+                perk("EXTRA_HP", 3),
+                perk("PROTECTION_FIRE", 1)
+            ]
+        );
+        assert_eq!(
+            summary.m_effects,
+            [
+                GameEffect {
+                    m_id: "BERSERK".to_string(),
+                    m_frames: 600
+                },
+                GameEffect {
+                    m_id: "PROTECTION_FIRE".to_string(),
+                    m_frames: -1
+                }
+            ]
+        );
+        assert!(summary.has_perk("extra_hp"));
+        assert!(summary.has_perk("PERK_PICKED_EDIT_WANDS_EVERYWHERE"));
+        assert!(!summary.has_perk("centipede"));
+    }
+
+    #[test]
+    fn missing_files_and_malformed_xml() {
+        let save = tempfile::tempdir().unwrap();
+        assert!(PerkSummary::from_save(save.path()).unwrap().is_none());
+
+        // either file is enough, a world without a WorldStateComponent has no perks
+        let save = save_with(&[("world_state.xml", "<Entity/>")]);
+        let summary = PerkSummary::from_save(save.path()).unwrap().unwrap();
+        assert!(summary.m_perks.is_empty() && summary.m_effects.is_empty());
+
+        let save = save_with(&[("player.xml", "<Entity><Entity></Entity>")]);
+        assert!(PerkSummary::from_save(save.path()).is_err());
+    }
+}
@@ -0,0 +1,196 @@
+use roxmltree::Document;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::{attr, component, read_xml};
+use crate::utils::error::*;
+
+// Noita stores hp in units of 25
+const HP_SCALE: f64 = 25.0;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Biome {
+    Surface,
+    Mines,
+    CoalPits,
+    SnowyDepths,
+    HiisiBase,
+    UndergroundJungle,
+    Vault,
+    TempleOfTheArt,
+    Laboratory,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlayerStats {
+    pub m_hp: f64,
+    pub m_max_hp: f64,
+    pub m_gold: i64,
+    pub m_x: f64,
+    pub m_y: f64,
+    pub m_biome: Biome,
+}
+
+impl Biome {
+    // main path only, the boundaries are the approximate depths of the holy mountains
+    fn from_depth(y: f64) -> Self {
+        match y {
+            y if y < 0.0 => Biome::Surface,
+            y if y < 1_300.0 => Biome::Mines,
+            y if y < 2_850.0 => Biome::CoalPits,
+            y if y < 4_900.0 => Biome::SnowyDepths,
+            y if y < 6_400.0 => Biome::HiisiBase,
+            y if y < 8_450.0 => Biome::UndergroundJungle,
+            y if y < 10_500.0 => Biome::Vault,
+            y if y < 12_550.0 => Biome::TempleOfTheArt,
+            _ => Biome::Laboratory,
+        }
+    }
+}
+
+impl std::fmt::Display for Biome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Biome::*;
+        let name = match self {
+            Surface => t!("biome_surface"),
+            Mines => t!("biome_mines"),
+            CoalPits => t!("biome_coal_pits"),
+            SnowyDepths => t!("biome_snowy_depths"),
+            HiisiBase => t!("biome_hiisi_base"),
+            UndergroundJungle => t!("biome_underground_jungle"),
+            Vault => t!("biome_vault"),
+            TempleOfTheArt => t!("biome_temple_of_the_art"),
+            Laboratory => t!("biome_laboratory"),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl PlayerStats {
+    pub fn from_save(save_dir: &Path) -> NSResult<Option<Self>> {
+        let Some(text) = read_xml(&save_dir.join("player.xml"))? else {
+            return Ok(None);
+        };
+        let doc = Document::parse(&text).explain(&t!("fail_parse_player_xml"))?;
+        let player = doc.root_element();
+
+        let damage_model = component(player, "DamageModelComponent");
+        let transform = component(player, "_Transform");
+        let (x, y) = (
+            transform
+                .and_then(|node| attr(node, "position.x"))
+                .unwrap_or(0.0),
+            transform
+                .and_then(|node| attr(node, "position.y"))
+                .unwrap_or(0.0),
+        );
+        Ok(Some(Self {
+            m_hp: damage_model
+                .and_then(|node| attr::<f64>(node, "hp"))
+                .unwrap_or(0.0)
+                * HP_SCALE,
+            m_max_hp: damage_model
+                .and_then(|node| attr::<f64>(node, "max_hp"))
+                .unwrap_or(0.0)
+                * HP_SCALE,
+            m_gold: component(player, "WalletComponent")
+                .and_then(|node| attr(node, "money"))
+                .unwrap_or(0),
+            m_x: x,
+            m_y: y,
+            m_biome: Biome::from_depth(y),
+        }))
+    }
+
+    #[inline]
+    pub fn get_depth(&self) -> f64 {
+        self.m_y.max(0.0)
+    }
+}
+
+// "{}" is a compact summary, "{:#}" lists every field
+impl std::fmt::Display for PlayerStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            write!(
+                f,
+                "{} {:.0}/{:.0}  {} {}  {} ({:.0}, {:.0})  {} {:.0}  {} {}",
+                t!("stat_hp"),
+                self.m_hp,
+                self.m_max_hp,
+                t!("stat_gold"),
+                self.m_gold,
+                t!("stat_position"),
+                self.m_x,
+                self.m_y,
+                t!("stat_depth"),
+                self.get_depth(),
+                t!("stat_biome"),
+                self.m_biome
+            )
+        } else {
+            write!(
+                f,
+                "{} {:.0}/{:.0}  ${}  {}",
+                t!("stat_hp"),
+                self.m_hp,
+                self.m_max_hp,
+                self.m_gold,
+                self.m_biome
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn save_with_player(xml: &str) -> TempDir {
+        let save = tempfile::tempdir().unwrap();
+        fs::write(save.path().join("player.xml"), xml).unwrap();
+        save
+    }
+
+    #[test]
+    fn reads_hp_gold_and_position() {
+        let save = save_with_player(
+            r#"<Entity name="DEBUG_NAME:player" tags="player_unit">
+                <_Transform position.x="227.5" position.y="3000.25" rotation="0"/>
+                <DamageModelComponent hp="4" max_hp="6" air_in_lungs="7"/>
+                <WalletComponent money="1234" money_spent="0"/>
+                <Entity name="inventory_quick"/>
+            </Entity>"#,
+        );
+        let stats = PlayerStats::from_save(save.path()).unwrap().unwrap();
+        assert_eq!(stats.m_hp, 100.0);
+        assert_eq!(stats.m_max_hp, 150.0);
+        assert_eq!(stats.m_gold, 1234);
+        assert_eq!((stats.m_x, stats.m_y), (227.5, 3000.25));
+        assert_eq!(stats.m_biome, Biome::SnowyDepths);
+    }
+
+    #[test]
+    fn missing_components_default_to_zero() {
+        let save = save_with_player(
+            r#"<Entity><_Transform position.x="oops" position.y="-40"/></Entity>"#,
+        );
+        let stats = PlayerStats::from_save(save.path()).unwrap().unwrap();
+        assert_eq!((stats.m_hp, stats.m_max_hp, stats.m_gold), (0.0, 0.0, 0));
+        assert_eq!((stats.m_x, stats.m_y), (0.0, -40.0));
+        assert_eq!(stats.m_biome, Biome::Surface);
+        assert_eq!(stats.get_depth(), 0.0);
+    }
+
+    #[test]
+    fn missing_file_and_malformed_xml() {
+        let save = tempfile::tempdir().unwrap();
+        assert!(PlayerStats::from_save(save.path()).unwrap().is_none());
+
+        let save = save_with_player("<Entity><DamageModelComponent hp=\"4\"></Entity>");
+        assert!(PlayerStats::from_save(save.path()).is_err());
+    }
+}
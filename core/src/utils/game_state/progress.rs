@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use crate::utils::error::*;
+
+// every persistent flag is an empty file named after it, e.g. "card_unlocked_black_hole"
+const FLAGS_FOLDER: &str = "flags";
+// bones wands that may show up in later runs
+const BONES_FOLDER: &str = "bones_new";
+// flags marking a spell as unlocked
+const SPELL_FLAG_PREFIX: &str = "card_unlocked_";
+
+// the unlock state kept in "save00/persistent"
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ProgressSummary {
+    pub m_flags: BTreeSet<String>,
+    pub m_bones: u32,
+}
+
+// what differs going from one progress state to another
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ProgressDiff {
+    pub m_gained: Vec<String>,
+    pub m_lost: Vec<String>,
+    pub m_old_bones: u32,
+    pub m_new_bones: u32,
+}
+
+impl ProgressSummary {
+    // an empty summary if `persistent_dir` doesn't exist, i.e. a fresh account
+    pub fn from_dir(persistent_dir: &Path) -> NSResult<Self> {
+        let list = |folder: &str| -> NSResult<Vec<String>> {
+            let path = persistent_dir.join(folder);
+            if !path.exists() {
+                return Ok(Vec::new());
+            }
+            let mut names = Vec::new();
+            for entry in fs::read_dir(&path)
+                .explain(&t!("fail_read_game_file", path = format!("{path:?}")))?
+            {
+                let entry = entry?;
+                names.push(entry.file_name().to_string_lossy().to_string());
+            }
+            Ok(names)
+        };
+        Ok(Self {
+            m_flags: list(FLAGS_FOLDER)?.into_iter().collect(),
+            m_bones: list(BONES_FOLDER)?.len() as u32,
+        })
+    }
+
+    pub fn spells(&self) -> usize {
+        self.m_flags
+            .iter()
+            .filter(|flag| flag.starts_with(SPELL_FLAG_PREFIX))
+            .count()
+    }
+
+    pub fn diff(&self, newer: &ProgressSummary) -> ProgressDiff {
+        ProgressDiff {
+            m_gained: newer.m_flags.difference(&self.m_flags).cloned().collect(),
+            m_lost: self.m_flags.difference(&newer.m_flags).cloned().collect(),
+            m_old_bones: self.m_bones,
+            m_new_bones: newer.m_bones,
+        }
+    }
+}
+
+impl std::fmt::Display for ProgressSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}  {} {}  {} {}",
+            t!("progress_flags"),
+            self.m_flags.len(),
+            t!("progress_spells"),
+            self.spells(),
+            t!("progress_bones"),
+            self.m_bones
+        )
+    }
+}
+
+// "{}" only counts the flags, "{:#}" lists them
+impl std::fmt::Display for ProgressDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{}: +{} -{}  {}: {} -> {}",
+            t!("progress_flags"),
+            self.m_gained.len(),
+            self.m_lost.len(),
+            t!("progress_bones"),
+            self.m_old_bones,
+            self.m_new_bones
+        )?;
+        if f.alternate() {
+            for flag in self.m_gained.iter() {
+                writeln!(f, "\t+ {}", flag)?;
+            }
+            for flag in self.m_lost.iter() {
+                writeln!(f, "\t- {}", flag)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,423 @@
+use roxmltree::Document;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+use super::attr;
+use crate::utils::error::*;
+
+// a single play session, read from "stats/sessions/<start time>_stats.xml"
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SessionStats {
+    // the file name without "_stats.xml", unique per session
+    pub m_session: String,
+    pub m_seed: Option<u32>,
+    pub m_playtime_secs: f64,
+    pub m_kills: u32,
+    pub m_gold: i64,
+    pub m_places_visited: u32,
+    pub m_dead: bool,
+    pub m_won: bool,
+    pub m_killed_by: Option<String>,
+    // deepest point known for the session, the position of death if the player died
+    pub m_depth: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct StatsSummary {
+    pub m_runs: usize,
+    pub m_wins: usize,
+    pub m_average_depth: Option<f64>,
+    pub m_most_common_killer: Option<String>,
+    pub m_total_playtime_secs: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct StatsReport {
+    pub m_summary: StatsSummary,
+    // every known session, oldest first
+    pub m_sessions: Vec<SessionStats>,
+}
+
+impl SessionStats {
+    // every session found in a save tree, an empty list if it has no "stats/sessions",
+    // files that can't be read or parsed are handed to `skipped` and left out
+    pub fn from_save(
+        save_dir: &Path,
+        mut skipped: impl FnMut(&Path, NSError),
+    ) -> NSResult<Vec<Self>> {
+        let sessions_dir = save_dir.join("stats").join("sessions");
+        if !sessions_dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut sessions = Vec::new();
+        for entry in fs::read_dir(&sessions_dir).explain(&t!(
+            "fail_read_game_file",
+            path = format!("{sessions_dir:?}")
+        ))? {
+            let path = entry?.path();
+            let Some(session) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix("_stats.xml"))
+            else {
+                continue;
+            };
+            match fs::read_to_string(&path)
+                .explain(&t!("fail_read_game_file", path = format!("{path:?}")))
+                .and_then(|text| Self::parse(session, &text))
+            {
+                Ok(stats) => sessions.push(stats),
+                Err(e) => skipped(&path, e),
+            }
+        }
+        Ok(sessions)
+    }
+
+    fn parse(session: &str, text: &str) -> NSResult<Self> {
+        let doc = Document::parse(text).explain(&t!("fail_parse_stats_xml"))?;
+        // the session's values live on the first element carrying them, usually <stats>
+        let stats = doc
+            .descendants()
+            .find(|node| node.has_attribute("playtime") || node.has_attribute("world_seed"))
+            .unwrap_or(doc.root_element());
+        let flag = |name: &str| attr::<i64>(stats, name).is_some_and(|value| value != 0);
+        let dead = flag("dead");
+        let depth = if dead {
+            attr::<f64>(stats, "death_pos.y")
+        } else {
+            None
+        };
+        Ok(Self {
+            m_session: session.to_string(),
+            m_seed: attr(stats, "world_seed"),
+            m_playtime_secs: attr(stats, "playtime").unwrap_or(0.0),
+            m_kills: attr(stats, "enemies_killed").unwrap_or(0),
+            m_gold: attr(stats, "gold_all").unwrap_or(0),
+            m_places_visited: attr(stats, "places_visited").unwrap_or(0),
+            m_dead: dead,
+            m_won: flag("win"),
+            m_killed_by: stats
+                .attribute("killed_by")
+                .map(str::trim)
+                .filter(|killer| dead && !killer.is_empty())
+                .map(str::to_string),
+            m_depth: depth.map(|y| y.max(0.0)),
+        })
+    }
+}
+
+// Noita writes a session file when a session ends and every session carries the seed of its
+// world. Continuing a run starts a new session in the same world, so the newest session belongs
+// to the run in the save if its world was generated before that session ended. A world newer
+// than every session is a run that hasn't finished a session yet, its seed is unknown.
+pub fn read_world_seed(save_dir: &Path) -> NSResult<Option<u32>> {
+    // a skipped newest session leaves an older one, which the world check below then rejects
+    let Some(newest) = SessionStats::from_save(save_dir, |_, _| {})?
+        .into_iter()
+        // names start with "YYYYMMDD-HHMMSS", so the newest sorts last
+        .max_by(|a, b| a.m_session.cmp(&b.m_session))
+    else {
+        return Ok(None);
+    };
+    let session_path = save_dir
+        .join("stats")
+        .join("sessions")
+        .join(format!("{}_stats.xml", newest.m_session));
+    let session_written = fs::metadata(&session_path)?.modified()?;
+
+    let world_dir = save_dir.join("world");
+    if !world_dir.exists() {
+        return Ok(None);
+    }
+    let mut world_generated = None;
+    for entry in fs::read_dir(&world_dir)
+        .explain(&t!("fail_read_game_file", path = format!("{world_dir:?}")))?
+    {
+        let metadata = entry?.metadata()?;
+        if metadata.is_file() {
+            let modified = metadata.modified()?;
+            world_generated =
+                Some(world_generated.map_or(modified, |oldest: SystemTime| oldest.min(modified)));
+        }
+    }
+    Ok(match world_generated {
+        Some(generated) if generated <= session_written => newest.m_seed,
+        _ => None,
+    })
+}
+
+impl StatsReport {
+    // the same session shows up in every snapshot taken after it, so sessions are merged by id
+    pub fn from_saves<'a>(
+        save_dirs: impl Iterator<Item = &'a Path>,
+        mut skipped: impl FnMut(&Path, NSError),
+    ) -> NSResult<Self> {
+        let mut sessions: BTreeMap<String, SessionStats> = BTreeMap::new();
+        for save_dir in save_dirs {
+            for session in SessionStats::from_save(save_dir, &mut skipped)? {
+                sessions.entry(session.m_session.clone()).or_insert(session);
+            }
+        }
+        let sessions: Vec<SessionStats> = sessions.into_values().collect();
+        Ok(Self {
+            m_summary: Self::summarize(&sessions),
+            m_sessions: sessions,
+        })
+    }
+
+    // continuing a run starts a new session with the same seed, so a run is one seed,
+    // sessions without a seed are counted as runs of their own
+    fn summarize(sessions: &[SessionStats]) -> StatsSummary {
+        let mut runs: HashMap<String, Vec<&SessionStats>> = HashMap::new();
+        for session in sessions {
+            let key = match session.m_seed {
+                Some(seed) => seed.to_string(),
+                None => session.m_session.clone(),
+            };
+            runs.entry(key).or_default().push(session);
+        }
+
+        let depths: Vec<f64> = runs
+            .values()
+            .filter_map(|run| {
+                run.iter()
+                    .filter_map(|session| session.m_depth)
+                    .reduce(f64::max)
+            })
+            .collect();
+        let mut killers: HashMap<&str, usize> = HashMap::new();
+        for killer in sessions
+            .iter()
+            .filter_map(|session| session.m_killed_by.as_deref())
+        {
+            *killers.entry(killer).or_default() += 1;
+        }
+
+        StatsSummary {
+            m_runs: runs.len(),
+            m_wins: runs
+                .values()
+                .filter(|run| run.iter().any(|session| session.m_won))
+                .count(),
+            m_average_depth: if depths.is_empty() {
+                None
+            } else {
+                Some(depths.iter().sum::<f64>() / depths.len() as f64)
+            },
+            m_most_common_killer: killers
+                .into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))
+                .map(|(killer, _)| killer.to_string()),
+            m_total_playtime_secs: sessions.iter().map(|session| session.m_playtime_secs).sum(),
+        }
+    }
+
+    pub fn to_json(&self) -> NSResult<String> {
+        serde_json::to_string_pretty(self).explain(&t!("fail_serialize_stats"))
+    }
+}
+
+impl std::fmt::Display for StatsSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let playtime = self.m_total_playtime_secs as u64;
+        let none = t!("perk_none").to_string();
+        writeln!(f, "{}: {}", t!("stats_runs"), self.m_runs)?;
+        writeln!(f, "{}: {}", t!("stats_wins"), self.m_wins)?;
+        writeln!(
+            f,
+            "{}: {}",
+            t!("stats_average_depth"),
+            self.m_average_depth
+                .map(|depth| format!("{depth:.0}"))
+                .unwrap_or(none.clone())
+        )?;
+        writeln!(
+            f,
+            "{}: {}",
+            t!("stats_most_common_killer"),
+            self.m_most_common_killer.as_deref().unwrap_or(&none)
+        )?;
+        write!(
+            f,
+            "{}: {}:{:02}:{:02}",
+            t!("stats_total_playtime"),
+            playtime / 3600,
+            playtime / 60 % 60,
+            playtime % 60
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn session_xml(seed: u32, extra: &str) -> String {
+        format!(r#"<stats world_seed="{seed}" playtime="90.5" {extra}/>"#)
+    }
+
+    fn write_session(save: &Path, session: &str, xml: &str) {
+        let dir = save.join("stats").join("sessions");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(format!("{session}_stats.xml")), xml).unwrap();
+    }
+
+    fn set_mtime(path: &Path, time: SystemTime) {
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    }
+
+    // a save whose newest session was written at `session_at` and whose world was generated at
+    // `world_at`
+    fn save_with_world(session_at: SystemTime, world_at: SystemTime) -> TempDir {
+        let save = tempfile::tempdir().unwrap();
+        write_session(save.path(), "20250920-100000", &session_xml(111, ""));
+        write_session(save.path(), "20250921-100000", &session_xml(222, ""));
+        set_mtime(
+            &save.path().join("stats/sessions/20250921-100000_stats.xml"),
+            session_at,
+        );
+        fs::create_dir(save.path().join("world")).unwrap();
+        for name in ["world_0_0.png_petri", "area_1.bin"] {
+            let path = save.path().join("world").join(name);
+            fs::write(&path, "").unwrap();
+            set_mtime(&path, world_at);
+        }
+        save
+    }
+
+    #[test]
+    fn parses_a_session() {
+        let stats = SessionStats::parse(
+            "20250920-100000",
+            r#"<Stats><stats world_seed="123456789" playtime="3600.5" enemies_killed="42"
+                             gold_all="1500" places_visited="4" dead="1" win="0"
+                             killed_by=" acid " death_pos.y="2400.5"/></Stats>"#,
+        )
+        .unwrap();
+        assert_eq!(stats.m_session, "20250920-100000");
+        assert_eq!(stats.m_seed, Some(123456789));
+        assert_eq!(stats.m_playtime_secs, 3600.5);
+        assert_eq!(
+            (stats.m_kills, stats.m_gold, stats.m_places_visited),
+            (42, 1500, 4)
+        );
+        assert!(stats.m_dead && !stats.m_won);
+        assert_eq!(stats.m_killed_by.as_deref(), Some("acid"));
+        assert_eq!(stats.m_depth, Some(2400.5));
+    }
+
+    #[test]
+    fn alive_sessions_have_no_killer_or_depth() {
+        let stats = SessionStats::parse(
+            "s",
+            r#"<stats playtime="10" dead="0" win="1" killed_by="acid" death_pos.y="5"/>"#,
+        )
+        .unwrap();
+        assert_eq!(stats.m_seed, None);
+        assert!(!stats.m_dead && stats.m_won);
+        assert_eq!((stats.m_killed_by, stats.m_depth), (None, None));
+    }
+
+    #[test]
+    fn seed_of_a_world_generated_before_the_newest_session_ended() {
+        let now = SystemTime::now();
+        let save = save_with_world(now, now - Duration::from_secs(600));
+        assert_eq!(read_world_seed(save.path()).unwrap(), Some(222));
+    }
+
+    #[test]
+    fn no_seed_during_the_first_session_of_a_run() {
+        let now = SystemTime::now();
+        let save = save_with_world(now - Duration::from_secs(600), now);
+        assert_eq!(read_world_seed(save.path()).unwrap(), None);
+    }
+
+    #[test]
+    fn no_seed_without_sessions_or_world() {
+        let save = tempfile::tempdir().unwrap();
+        assert_eq!(read_world_seed(save.path()).unwrap(), None);
+
+        write_session(save.path(), "20250920-100000", &session_xml(111, ""));
+        assert_eq!(read_world_seed(save.path()).unwrap(), None);
+    }
+
+    #[test]
+    fn malformed_sessions_are_skipped() {
+        let save = tempfile::tempdir().unwrap();
+        write_session(save.path(), "20250920-100000", &session_xml(111, ""));
+        write_session(save.path(), "20250921-100000", "<stats world_seed=");
+        // not a session file
+        fs::write(save.path().join("stats/sessions/notes.txt"), "").unwrap();
+
+        let mut skipped = Vec::new();
+        let sessions = SessionStats::from_save(save.path(), |path, _| {
+            skipped.push(path.file_name().unwrap().to_owned())
+        })
+        .unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].m_seed, Some(111));
+        assert_eq!(skipped, ["20250921-100000_stats.xml"]);
+    }
+
+    #[test]
+    fn report_merges_sessions_and_groups_runs_by_seed() {
+        let older = tempfile::tempdir().unwrap();
+        write_session(
+            older.path(),
+            "20250920-100000",
+            &session_xml(111, r#"dead="1" killed_by="acid" death_pos.y="1000""#),
+        );
+        let newer = tempfile::tempdir().unwrap();
+        write_session(
+            newer.path(),
+            "20250920-100000",
+            &session_xml(111, r#"dead="1" killed_by="acid" death_pos.y="1000""#),
+        );
+        write_session(newer.path(), "20250921-100000", &session_xml(222, ""));
+        write_session(
+            newer.path(),
+            "20250921-120000",
+            &session_xml(
+                222,
+                r#"dead="1" win="1" killed_by="lava" death_pos.y="3000""#,
+            ),
+        );
+        write_session(newer.path(), "20250922-100000", "<stats playtime=\"9\"/>");
+
+        let report =
+            StatsReport::from_saves([older.path(), newer.path()].into_iter(), |_, _| {}).unwrap();
+        let sessions: Vec<&str> = report
+            .m_sessions
+            .iter()
+            .map(|session| session.m_session.as_str())
+            .collect();
+        assert_eq!(
+            sessions,
+            [
+                "20250920-100000",
+                "20250921-100000",
+                "20250921-120000",
+                "20250922-100000"
+            ]
+        );
+        let summary = &report.m_summary;
+        // seeds 111 and 222, plus the session without a seed
+        assert_eq!(summary.m_runs, 3);
+        assert_eq!(summary.m_wins, 1);
+        assert_eq!(summary.m_average_depth, Some(2000.0));
+        // a tie is broken by name
+        assert_eq!(summary.m_most_common_killer.as_deref(), Some("acid"));
+        assert_eq!(summary.m_total_playtime_secs, 90.5 * 3.0 + 9.0);
+    }
+}
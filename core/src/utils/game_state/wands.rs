@@ -0,0 +1,267 @@
+use roxmltree::{Document, Node};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::{FRAMES_PER_SECOND, attr, component, read_xml};
+use crate::utils::error::*;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WandSummary {
+    pub m_name: String,
+    // position in the quick inventory
+    pub m_slot: u32,
+    pub m_shuffle: bool,
+    pub m_spells_per_cast: u32,
+    pub m_cast_delay_frames: i32,
+    pub m_recharge_frames: i32,
+    pub m_mana: f64,
+    pub m_mana_max: f64,
+    pub m_mana_charge_speed: f64,
+    pub m_capacity: u32,
+    pub m_spread_degrees: f64,
+    // action ids of the spells in slot order, e.g. "LIGHT_BULLET"
+    pub m_spells: Vec<String>,
+    pub m_always_cast: Vec<String>,
+}
+
+impl WandSummary {
+    // every wand held by the player, an empty list if the save has no "player.xml"
+    pub fn from_save(save_dir: &Path) -> NSResult<Vec<Self>> {
+        let Some(text) = read_xml(&save_dir.join("player.xml"))? else {
+            return Ok(Vec::new());
+        };
+        let doc = Document::parse(&text).explain(&t!("fail_parse_player_xml"))?;
+        let Some(inventory) = doc
+            .root_element()
+            .children()
+            .find(|node| node.attribute("name") == Some("inventory_quick"))
+        else {
+            return Ok(Vec::new());
+        };
+
+        let mut wands: Vec<Self> = inventory
+            .children()
+            .filter(|node| Self::has_tag(*node, "wand"))
+            .map(Self::from_entity)
+            .collect();
+        wands.sort_by_key(|wand| wand.m_slot);
+        Ok(wands)
+    }
+
+    // same wand and spells, ignoring the mana currently left in it
+    pub fn same_build(&self, other: &Self) -> bool {
+        *self
+            == Self {
+                m_mana: self.m_mana,
+                ..other.clone()
+            }
+    }
+
+    fn has_tag(entity: Node, tag: &str) -> bool {
+        entity.has_tag_name("Entity")
+            && entity
+                .attribute("tags")
+                .is_some_and(|tags| tags.split(',').any(|item| item.trim() == tag))
+    }
+
+    fn slot_of(entity: Node) -> u32 {
+        component(entity, "ItemComponent")
+            .and_then(|node| attr(node, "inventory_slot.x"))
+            .unwrap_or(0)
+    }
+
+    fn from_entity(wand: Node) -> Self {
+        let ability = component(wand, "AbilityComponent");
+        let gun_config = ability.and_then(|node| component(node, "gun_config"));
+        let gunaction_config = ability.and_then(|node| component(node, "gunaction_config"));
+        let ability_attr = |name: &str| ability.and_then(|node| attr::<f64>(node, name));
+
+        let mut spells: Vec<(u32, String, bool)> = wand
+            .children()
+            .filter(|node| node.has_tag_name("Entity"))
+            .filter_map(|spell| {
+                let action_id = component(spell, "ItemActionComponent")?
+                    .attribute("action_id")?
+                    .to_string();
+                let always_cast = component(spell, "ItemComponent")
+                    .and_then(|node| node.attribute("permanently_attached"))
+                    .is_some_and(|value| value == "1");
+                Some((Self::slot_of(spell), action_id, always_cast))
+            })
+            .collect();
+        spells.sort_by_key(|(slot, _, _)| *slot);
+
+        Self {
+            m_name: ability
+                .and_then(|node| node.attribute("ui_name"))
+                .unwrap_or_default()
+                .to_string(),
+            m_slot: Self::slot_of(wand),
+            m_shuffle: gun_config
+                .and_then(|node| node.attribute("shuffle_deck_when_empty"))
+                .is_some_and(|value| value == "1"),
+            m_spells_per_cast: gun_config
+                .and_then(|node| attr(node, "actions_per_round"))
+                .unwrap_or(1),
+            m_cast_delay_frames: gunaction_config
+                .and_then(|node| attr(node, "fire_rate_wait"))
+                .unwrap_or(0),
+            m_recharge_frames: gun_config
+                .and_then(|node| attr(node, "reload_time"))
+                .unwrap_or(0),
+            m_mana: ability_attr("mana").unwrap_or(0.0),
+            m_mana_max: ability_attr("mana_max").unwrap_or(0.0),
+            m_mana_charge_speed: ability_attr("mana_charge_speed").unwrap_or(0.0),
+            m_capacity: gun_config
+                .and_then(|node| attr(node, "deck_capacity"))
+                .unwrap_or(0),
+            m_spread_degrees: gunaction_config
+                .and_then(|node| attr(node, "spread_degrees"))
+                .unwrap_or(0.0),
+            m_always_cast: spells
+                .iter()
+                .filter(|(_, _, always_cast)| *always_cast)
+                .map(|(_, id, _)| id.clone())
+                .collect(),
+            m_spells: spells
+                .into_iter()
+                .filter(|(_, _, always_cast)| !*always_cast)
+                .map(|(_, id, _)| id)
+                .collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for WandSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "[{}] {}  {}: {}  {}: {}  {}: {:.2}s  {}: {:.2}s",
+            self.m_slot + 1,
+            self.m_name,
+            t!("wand_shuffle"),
+            if self.m_shuffle { t!("yes") } else { t!("no") },
+            t!("wand_spells_per_cast"),
+            self.m_spells_per_cast,
+            t!("wand_cast_delay"),
+            self.m_cast_delay_frames as f64 / FRAMES_PER_SECOND,
+            t!("wand_recharge"),
+            self.m_recharge_frames as f64 / FRAMES_PER_SECOND,
+        )?;
+        writeln!(
+            f,
+            "    {}: {:.0}/{:.0} (+{:.0}/s)  {}: {}  {}: {:.1}°",
+            t!("wand_mana"),
+            self.m_mana,
+            self.m_mana_max,
+            self.m_mana_charge_speed,
+            t!("wand_capacity"),
+            self.m_capacity,
+            t!("wand_spread"),
+            self.m_spread_degrees,
+        )?;
+        if !self.m_always_cast.is_empty() {
+            writeln!(
+                f,
+                "    {}: {}",
+                t!("wand_always_cast"),
+                self.m_always_cast.join(", ")
+            )?;
+        }
+        write!(f, "    {}: {}", t!("wand_spells"), self.m_spells.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn save_with_player(xml: &str) -> TempDir {
+        let save = tempfile::tempdir().unwrap();
+        fs::write(save.path().join("player.xml"), xml).unwrap();
+        save
+    }
+
+    fn spell(slot: u32, action_id: &str, always_cast: bool) -> String {
+        format!(
+            r#"<Entity tags="card_action">
+                <ItemComponent inventory_slot.x="{slot}" permanently_attached="{}"/>
+                <ItemActionComponent action_id="{action_id}"/>
+            </Entity>"#,
+            always_cast as u8
+        )
+    }
+
+    #[test]
+    fn reads_wands_in_slot_order() {
+        let save = save_with_player(&format!(
+            r#"<Entity tags="player_unit">
+                <Entity name="inventory_quick">
+                    <Entity tags="teleportable_NOT,wand,item">
+                        <ItemComponent inventory_slot.x="1"/>
+                        <AbilityComponent ui_name="Bolt staff" mana="80.5" mana_max="300"
+                                          mana_charge_speed="120">
+                            <gun_config shuffle_deck_when_empty="1" actions_per_round="2"
+                                        reload_time="30" deck_capacity="6"/>
+                            <gunaction_config fire_rate_wait="-5" spread_degrees="2.5"/>
+                        </AbilityComponent>
+                        {}{}{}
+                    </Entity>
+                    <Entity tags="wand">
+                        <ItemComponent inventory_slot.x="0"/>
+                        <AbilityComponent ui_name="Starter"/>
+                    </Entity>
+                    <Entity tags="potion,item"><ItemComponent inventory_slot.x="2"/></Entity>
+                </Entity>
+            </Entity>"#,
+            spell(1, "SPARK_BOLT", false),
+            spell(0, "LIGHT_BULLET", false),
+            spell(0, "DAMAGE", true)
+        ));
+        let wands = WandSummary::from_save(save.path()).unwrap();
+        assert_eq!(wands.len(), 2);
+
+        // missing values fall back to defaults
+        assert_eq!(wands[0].m_name, "Starter");
+        assert_eq!(wands[0].m_slot, 0);
+        assert_eq!(wands[0].m_spells_per_cast, 1);
+        assert!(!wands[0].m_shuffle);
+        assert!(wands[0].m_spells.is_empty());
+
+        let bolt = &wands[1];
+        assert_eq!(bolt.m_name, "Bolt staff");
+        assert_eq!(bolt.m_slot, 1);
+        assert!(bolt.m_shuffle);
+        assert_eq!(bolt.m_spells_per_cast, 2);
+        assert_eq!(bolt.m_cast_delay_frames, -5);
+        assert_eq!(bolt.m_recharge_frames, 30);
+        assert_eq!((bolt.m_mana, bolt.m_mana_max), (80.5, 300.0));
+        assert_eq!(bolt.m_mana_charge_speed, 120.0);
+        assert_eq!(bolt.m_capacity, 6);
+        assert_eq!(bolt.m_spread_degrees, 2.5);
+        assert_eq!(bolt.m_spells, ["LIGHT_BULLET", "SPARK_BOLT"]);
+        assert_eq!(bolt.m_always_cast, ["DAMAGE"]);
+
+        let recharged = WandSummary {
+            m_mana: 300.0,
+            ..bolt.clone()
+        };
+        assert!(bolt.same_build(&recharged));
+        assert!(!bolt.same_build(&wands[0]));
+    }
+
+    #[test]
+    fn missing_inventory_file_and_malformed_xml() {
+        let save = tempfile::tempdir().unwrap();
+        assert!(WandSummary::from_save(save.path()).unwrap().is_empty());
+
+        let save = save_with_player(r#"<Entity tags="player_unit"/>"#);
+        assert!(WandSummary::from_save(save.path()).unwrap().is_empty());
+
+        let save =
+            save_with_player(r#"<Entity tags="player_unit"><Entity name="inventory_quick">"#);
+        assert!(WandSummary::from_save(save.path()).is_err());
+    }
+}
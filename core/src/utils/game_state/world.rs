@@ -0,0 +1,197 @@
+use roxmltree::Document;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::{attr, component, read_xml, world_flags};
+use crate::utils::error::*;
+
+// run flags set when a boss or miniboss dies, e.g. "boss_centipede" for Kolmisilmä
+const BOSS_FLAG_PREFIXES: [&str; 2] = ["boss_", "miniboss_"];
+// every holy mountain collapses behind the player once they leave it
+const HOLY_MOUNTAIN_FLAG_PREFIX: &str = "temple_collapsed";
+// WorldStateComponent switches worth pointing out in a save list
+const NOTABLE_WORLD_SWITCHES: [&str; 6] = [
+    "EVERYTHING_TO_GOLD",
+    "INFINITE_GOLD_HAPPENING",
+    "ENDING_HAPPINESS_HAPPENING",
+    "ENDING_HAPPINESS",
+    "mods_have_been_active_during_this_run",
+    "twitch_has_been_active_during_this_run",
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct WorldProgress {
+    pub m_orbs: u32,
+    pub m_bosses: Vec<String>,
+    pub m_holy_mountains: u32,
+    pub m_day: u32,
+    // fraction of the in-game day, 0.0 to 1.0
+    pub m_time: f64,
+    pub m_flags: Vec<String>,
+}
+
+impl WorldProgress {
+    // None if the save has no "world_state.xml"
+    pub fn from_save(save_dir: &Path) -> NSResult<Option<Self>> {
+        let Some(text) = read_xml(&save_dir.join("world_state.xml"))? else {
+            return Ok(None);
+        };
+        let doc = Document::parse(&text).explain(&t!("fail_parse_world_state_xml"))?;
+        let Some(world_state) = component(doc.root_element(), "WorldStateComponent") else {
+            return Ok(Some(Self::default()));
+        };
+
+        let run_flags: Vec<&str> = world_flags(world_state).collect();
+        let mut bosses: Vec<String> = run_flags
+            .iter()
+            .filter(|flag| {
+                BOSS_FLAG_PREFIXES
+                    .iter()
+                    .any(|prefix| flag.to_lowercase().starts_with(prefix))
+            })
+            .map(|flag| flag.to_string())
+            .collect();
+        bosses.sort();
+        bosses.dedup();
+        let holy_mountains = run_flags
+            .iter()
+            .filter(|flag| flag.to_lowercase().starts_with(HOLY_MOUNTAIN_FLAG_PREFIX))
+            .count() as u32;
+
+        Ok(Some(Self {
+            m_orbs: component(world_state, "orbs_found_thisrun")
+                .map(|node| node.children().filter(|node| node.is_element()).count() as u32)
+                .unwrap_or(0),
+            m_bosses: bosses,
+            m_holy_mountains: holy_mountains,
+            m_day: attr(world_state, "day_count").unwrap_or(0),
+            m_time: attr(world_state, "time").unwrap_or(0.0),
+            m_flags: NOTABLE_WORLD_SWITCHES
+                .iter()
+                .filter(|name| {
+                    world_state
+                        .attribute(**name)
+                        .is_some_and(|value| matches!(value.trim(), "1" | "true"))
+                })
+                .map(|name| name.to_string())
+                .collect(),
+        }))
+    }
+
+    // in-game clock as "HH:MM"
+    fn clock(&self) -> String {
+        let minutes = (self.m_time.clamp(0.0, 1.0) * 24.0 * 60.0) as u32 % (24 * 60);
+        format!("{:02}:{:02}", minutes / 60, minutes % 60)
+    }
+}
+
+// "{}" is a compact progress column, "{:#}" also lists the bosses and notable flags
+impl std::fmt::Display for WorldProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}  {} {}  {} {}  {} {} {}",
+            t!("world_orbs"),
+            self.m_orbs,
+            t!("world_bosses"),
+            self.m_bosses.len(),
+            t!("world_holy_mountains"),
+            self.m_holy_mountains,
+            t!("world_day"),
+            self.m_day,
+            self.clock()
+        )?;
+        if f.alternate() {
+            let none = t!("perk_none").to_string();
+            write!(
+                f,
+                "\n\t{}: {}\n\t{}: {}",
+                t!("world_bosses"),
+                if self.m_bosses.is_empty() {
+                    none.clone()
+                } else {
+                    self.m_bosses.join(", ")
+                },
+                t!("world_flags"),
+                if self.m_flags.is_empty() {
+                    none
+                } else {
+                    self.m_flags.join(", ")
+                }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn save_with_world_state(xml: &str) -> TempDir {
+        let save = tempfile::tempdir().unwrap();
+        fs::write(save.path().join("world_state.xml"), xml).unwrap();
+        save
+    }
+
+    #[test]
+    fn reads_orbs_bosses_and_clock() {
+        let save = save_with_world_state(
+            r#"<Entity>
+                <WorldStateComponent day_count="3" time="0.75" EVERYTHING_TO_GOLD="1"
+                                     ENDING_HAPPINESS="0" mods_have_been_active_during_this_run="true">
+                    <flags>
+                        <string contents="miniboss_dragon"/>
+                        <string contents="boss_centipede"/>
+                        <string>boss_centipede</string>
+                        <string contents="temple_collapsed_1"/>
+                        <string contents="TEMPLE_COLLAPSED_2"/>
+                        <string contents="PERK_PICKED_EXTRA_HP"/>
+                    </flags>
+                    <orbs_found_thisrun>
+                        <primitive value="0"/>
+                        <primitive value="4"/>
+                    </orbs_found_thisrun>
+                </WorldStateComponent>
+            </Entity>"#,
+        );
+        let world = WorldProgress::from_save(save.path()).unwrap().unwrap();
+        assert_eq!(world.m_orbs, 2);
+        assert_eq!(world.m_bosses, ["boss_centipede", "miniboss_dragon"]);
+        assert_eq!(world.m_holy_mountains, 2);
+        assert_eq!(world.m_day, 3);
+        assert_eq!(world.clock(), "18:00");
+        assert_eq!(
+            world.m_flags,
+            [
+                "EVERYTHING_TO_GOLD",
+                "mods_have_been_active_during_this_run"
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_file_component_and_malformed_xml() {
+        let save = tempfile::tempdir().unwrap();
+        assert!(WorldProgress::from_save(save.path()).unwrap().is_none());
+
+        let save = save_with_world_state(r#"<Entity><WorldStateComponent time="x"/></Entity>"#);
+        let world = WorldProgress::from_save(save.path()).unwrap().unwrap();
+        assert_eq!((world.m_orbs, world.m_day, world.m_time), (0, 0, 0.0));
+        assert!(world.m_bosses.is_empty() && world.m_flags.is_empty());
+
+        let save = save_with_world_state("<Entity/>");
+        assert_eq!(
+            WorldProgress::from_save(save.path())
+                .unwrap()
+                .unwrap()
+                .m_day,
+            0
+        );
+
+        let save = save_with_world_state("<Entity><WorldStateComponent>");
+        assert!(WorldProgress::from_save(save.path()).is_err());
+    }
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+#[cfg(target_os = "linux")]
+use std::path::PathBuf;
+
+#[cfg(target_os = "linux")]
+use super::error::*;
+#[cfg(target_os = "linux")]
+use super::path_decision::PathDecision;
+
+// how Noita is run on Linux, which also decides where its live save is
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LauncherKind {
+    // Proton from a Steam library, the save is in Noita's "compatdata" prefix
+    #[default]
+    Steam,
+    // any Wine prefix, e.g. the GOG build installed by hand or with Lutris
+    Wine,
+}
+
+// whether "startgame" runs Noita itself or asks the Steam client to
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchMode {
+    // faster with large saves, but without the overlay, achievements or play time
+    #[default]
+    Direct,
+    // "steam -applaunch 881100", Steam is started first if it isn't running
+    Steam,
+}
+
+impl LaunchMode {
+    // e.g. "steam" from the command line
+    pub fn parse(mode: &str) -> Option<Self> {
+        match mode {
+            "direct" => Some(LaunchMode::Direct),
+            "steam" => Some(LaunchMode::Steam),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub trait Launcher: std::fmt::Debug + Send {
+    fn kind(&self) -> LauncherKind;
+
+    // the live "save00" of the Noita this launcher runs
+    fn live_save_path(&self) -> PathBuf;
+
+    // the folder holding "noita.exe"
+    fn noita_dir(&self) -> PathBuf;
+
+    fn launch(&self) -> NSComResult;
+
+    // asks the Steam client to start Noita, only a Steam install can do that
+    fn launch_with_client(&self) -> NSComResult {
+        throw(&t!("launch_mode_needs_steam"))
+    }
+
+    // every path the launcher relies on and why it was chosen
+    fn path_decisions(&self) -> Vec<PathDecision>;
+}
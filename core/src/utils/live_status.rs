@@ -0,0 +1,37 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+// how the live save relates to the newest snapshot
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "m_status", rename_all = "snake_case")]
+pub enum LiveStatus {
+    NoSnapshot,
+    InSync { m_name: String },
+    ModifiedSince { m_name: String, m_timestamp: i64 },
+    // the snapshot was taken before manifests were recorded, so there is nothing to compare with
+    Unknown { m_name: String },
+}
+
+impl std::fmt::Display for LiveStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = match self {
+            LiveStatus::NoSnapshot => t!("live_status_no_snapshot"),
+            LiveStatus::InSync { m_name } => t!("live_status_in_sync", save_name = m_name),
+            LiveStatus::ModifiedSince {
+                m_name,
+                m_timestamp,
+            } => t!(
+                "live_status_modified_since",
+                save_name = m_name,
+                time = DateTime::from_timestamp(*m_timestamp, 0)
+                    .map(|time| time
+                        .with_timezone(&Local)
+                        .format(&format!("{} {}", t!("date_format"), t!("time_format")))
+                        .to_string())
+                    .unwrap_or_default()
+            ),
+            LiveStatus::Unknown { m_name } => t!("live_status_unknown", save_name = m_name),
+        };
+        write!(f, "{}", status)
+    }
+}
@@ -0,0 +1,343 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use super::error::*;
+use super::snapshot_filter::PathFilter;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    m_size: u64,
+    m_mtime_ns: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    m_hash: Option<u64>,
+}
+
+// paths of the files that differ between two manifests
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FileDiff {
+    pub m_added: Vec<String>,
+    pub m_removed: Vec<String>,
+    pub m_modified: Vec<String>,
+}
+
+// a lightweight description of every file in a save tree, used to tell whether
+// the live save has changed since a snapshot was taken
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct Manifest {
+    m_files: BTreeMap<String, ManifestEntry>,
+}
+
+impl ManifestEntry {
+    fn same_as(&self, other: &ManifestEntry) -> bool {
+        if self.m_size != other.m_size {
+            return false;
+        }
+        match (self.m_hash, other.m_hash) {
+            (Some(a), Some(b)) => a == b,
+            _ => self.m_mtime_ns == other.m_mtime_ns,
+        }
+    }
+}
+
+impl Manifest {
+    // only the files `filter` keeps are listed
+    pub fn build(root: &Path, with_hashes: bool, filter: &PathFilter) -> NSResult<Self> {
+        let mut manifest = Self::default();
+        if root.exists() {
+            manifest.walk(root, root, with_hashes, filter)?;
+        }
+        Ok(manifest)
+    }
+
+    fn walk(
+        &mut self,
+        root: &Path,
+        dir: &Path,
+        with_hashes: bool,
+        filter: &PathFilter,
+    ) -> NSComResult {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_dir() {
+                self.walk(root, &entry.path(), with_hashes, filter)?;
+                continue;
+            }
+            let key = Self::relative_key(root, &entry.path());
+            if !filter.keeps(&key) {
+                continue;
+            }
+            let mtime_ns = metadata
+                .modified()?
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(0);
+            let hash = if with_hashes {
+                Some(Self::hash_file(&entry.path())?)
+            } else {
+                None
+            };
+            self.m_files.insert(
+                key,
+                ManifestEntry {
+                    m_size: metadata.len(),
+                    m_mtime_ns: mtime_ns,
+                    m_hash: hash,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    // keys always use '/' so that manifests are comparable across platforms
+    pub fn relative_key(root: &Path, path: &Path) -> String {
+        path.strip_prefix(root)
+            .unwrap_or(path)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    // 64-bit FNV-1a, stable across builds unlike std's DefaultHasher
+    fn hash_file(path: &Path) -> NSResult<u64> {
+        let mut reader = BufReader::new(fs::File::open(path)?);
+        let mut buffer = [0u8; 8192];
+        let mut hash = 0xcbf29ce484222325u64;
+        loop {
+            let n = reader.read(&mut buffer)?;
+            if n == 0 {
+                break;
+            }
+            for &byte in &buffer[..n] {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        }
+        Ok(hash)
+    }
+
+    pub fn load(path: &Path) -> NSResult<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let file = fs::File::open(path).explain(&t!("fail_read_manifest"))?;
+        let manifest =
+            serde_json::from_reader(BufReader::new(file)).explain(&t!("fail_read_manifest"))?;
+        Ok(Some(manifest))
+    }
+
+    pub fn write(&self, path: &Path) -> NSComResult {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).explain(&t!("fail_write_manifest"))?;
+        }
+        let file = fs::File::create(path).explain(&t!("fail_write_manifest"))?;
+        serde_json::to_writer(file, self).explain(&t!("fail_write_manifest"))?;
+        Ok(())
+    }
+
+    // relative paths of every recorded file
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.m_files.keys().map(String::as_str)
+    }
+
+    #[inline]
+    pub fn contains(&self, path: &str) -> bool {
+        self.m_files.contains_key(path)
+    }
+
+    #[inline]
+    pub fn has_hashes(&self) -> bool {
+        self.m_files.values().any(|entry| entry.m_hash.is_some())
+    }
+
+    // what changed going from `self` to `newer`
+    pub fn diff(&self, newer: &Manifest) -> FileDiff {
+        let mut diff = FileDiff::default();
+        for (path, entry) in &self.m_files {
+            match newer.m_files.get(path) {
+                None => diff.m_removed.push(path.clone()),
+                Some(newer_entry) if !entry.same_as(newer_entry) => {
+                    diff.m_modified.push(path.clone())
+                }
+                Some(_) => (),
+            }
+        }
+        diff.m_added = newer
+            .m_files
+            .keys()
+            .filter(|path| !self.m_files.contains_key(*path))
+            .cloned()
+            .collect();
+        diff
+    }
+
+    // whether the tree at `root` still matches this recorded manifest, sizes and mtimes are
+    // compared first and files are only hashed when both match and a hash was recorded
+    pub fn matches_tree(&self, root: &Path, filter: &PathFilter) -> NSBoolResult {
+        let live = Self::build(root, false, filter)?;
+        if live.m_files.len() != self.m_files.len() {
+            return Ok(false);
+        }
+        for (path, entry) in &self.m_files {
+            match live.m_files.get(path) {
+                Some(live_entry)
+                    if live_entry.m_size == entry.m_size
+                        && live_entry.m_mtime_ns == entry.m_mtime_ns => {}
+                _ => return Ok(false),
+            }
+        }
+        for (path, entry) in &self.m_files {
+            if let Some(hash) = entry.m_hash
+                && Self::hash_file(&root.join(path))? != hash
+            {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    fn write(root: &Path, relative: &str, content: &str, mtime: SystemTime) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+    }
+
+    fn build(root: &Path, with_hashes: bool) -> Manifest {
+        Manifest::build(root, with_hashes, &PathFilter::default()).unwrap()
+    }
+
+    fn same(old: &Manifest, new: &Manifest) -> bool {
+        let diff = old.diff(new);
+        diff.m_added.is_empty() && diff.m_removed.is_empty() && diff.m_modified.is_empty()
+    }
+
+    #[test]
+    fn size_change_is_a_modification() {
+        let dir = tempfile::tempdir().unwrap();
+        let mtime = SystemTime::now() - Duration::from_secs(60);
+        write(dir.path(), "world/area.bin", "area", mtime);
+        let before = build(dir.path(), true);
+        // the size alone gives it away even with the old mtime
+        write(dir.path(), "world/area.bin", "larger area", mtime);
+        let after = build(dir.path(), true);
+
+        assert!(!same(&before, &after));
+        let diff = before.diff(&after);
+        assert_eq!(diff.m_modified, ["world/area.bin"]);
+        assert!(diff.m_added.is_empty() && diff.m_removed.is_empty());
+    }
+
+    #[test]
+    fn mtime_only_change_depends_on_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let mtime = SystemTime::now() - Duration::from_secs(60);
+        write(dir.path(), "player.xml", "<Entity/>", mtime);
+        let (hashed, plain) = (build(dir.path(), true), build(dir.path(), false));
+        write(dir.path(), "player.xml", "<Entity/>", SystemTime::now());
+
+        // with hashes on both sides the content decides
+        assert!(same(&hashed, &build(dir.path(), true)));
+        assert!(hashed.diff(&build(dir.path(), true)).m_modified.is_empty());
+        // a side without hashes falls back to the mtime
+        assert!(!same(&plain, &build(dir.path(), false)));
+        assert!(!same(&hashed, &build(dir.path(), false)));
+        assert_eq!(
+            plain.diff(&build(dir.path(), false)).m_modified,
+            ["player.xml"]
+        );
+    }
+
+    #[test]
+    fn same_size_and_mtime_with_different_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let mtime = SystemTime::now() - Duration::from_secs(60);
+        write(dir.path(), "player.xml", "aaaa", mtime);
+        let (hashed, plain) = (build(dir.path(), true), build(dir.path(), false));
+        write(dir.path(), "player.xml", "bbbb", mtime);
+
+        assert!(!same(&hashed, &build(dir.path(), true)));
+        // only hashes can tell
+        assert!(same(&plain, &build(dir.path(), false)));
+    }
+
+    #[test]
+    fn added_and_removed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let mtime = SystemTime::now() - Duration::from_secs(60);
+        write(dir.path(), "a.xml", "a", mtime);
+        write(dir.path(), "stats/b.xml", "b", mtime);
+        let before = build(dir.path(), false);
+        fs::remove_file(dir.path().join("stats/b.xml")).unwrap();
+        write(dir.path(), "world/c.bin", "c", mtime);
+        let after = build(dir.path(), false);
+
+        assert!(!same(&before, &after));
+        let diff = before.diff(&after);
+        assert_eq!(diff.m_added, ["world/c.bin"]);
+        assert_eq!(diff.m_removed, ["stats/b.xml"]);
+        assert!(diff.m_modified.is_empty());
+        assert!(!same(&after, &before));
+    }
+
+    #[test]
+    fn filter_and_missing_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let mtime = SystemTime::now();
+        write(dir.path(), "a.xml", "a", mtime);
+        write(dir.path(), "stats/b.xml", "b", mtime);
+        let filter = crate::utils::snapshot_filter::SnapshotFilter {
+            include: Vec::new(),
+            exclude: vec!["stats".to_string()],
+        }
+        .compile()
+        .unwrap();
+        let manifest = Manifest::build(dir.path(), false, &filter).unwrap();
+        assert_eq!(manifest.paths().collect::<Vec<_>>(), ["a.xml"]);
+
+        let missing = build(&dir.path().join("missing"), false);
+        assert!(same(&missing, &Manifest::default()));
+    }
+
+    #[test]
+    fn matches_tree_checks_metadata_before_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let mtime = SystemTime::now() - Duration::from_secs(60);
+        write(dir.path(), "player.xml", "aaaa", mtime);
+        write(dir.path(), "world/area.bin", "area", mtime);
+        let (hashed, plain) = (build(dir.path(), true), build(dir.path(), false));
+        let filter = PathFilter::default();
+        assert!(hashed.matches_tree(dir.path(), &filter).unwrap());
+        assert!(plain.matches_tree(dir.path(), &filter).unwrap());
+
+        // same size and mtime, only the recorded hash can tell
+        write(dir.path(), "player.xml", "bbbb", mtime);
+        assert!(!hashed.matches_tree(dir.path(), &filter).unwrap());
+        assert!(plain.matches_tree(dir.path(), &filter).unwrap());
+
+        // a touched file counts as changed without hashing it
+        write(dir.path(), "player.xml", "aaaa", SystemTime::now());
+        assert!(!hashed.matches_tree(dir.path(), &filter).unwrap());
+        assert!(!plain.matches_tree(dir.path(), &filter).unwrap());
+
+        write(dir.path(), "player.xml", "aaaa", mtime);
+        write(dir.path(), "world/new.bin", "new", mtime);
+        assert!(!hashed.matches_tree(dir.path(), &filter).unwrap());
+    }
+}
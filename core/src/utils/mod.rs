@@ -1,4 +1,25 @@
+pub mod auto_save_scheduler;
+pub mod diff;
 pub mod error;
 pub mod file_operator;
+pub mod game_build;
+pub mod game_state;
+pub mod launcher;
+pub mod live_status;
+pub mod manifest;
+pub mod mod_profile;
 pub mod output_manager;
+pub mod path_decision;
+pub mod process;
+pub mod progress_profile;
+pub mod restore_mode;
+pub mod retention;
 pub mod save_infos;
+pub mod settings;
+pub mod snapshot_filter;
+#[cfg(target_os = "linux")]
+pub mod steam;
+#[cfg(target_os = "linux")]
+pub mod vdf;
+#[cfg(target_os = "linux")]
+pub mod wine;
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+use super::game_state::mods::ModList;
+
+// a named set of enabled mods, the captured mod settings are kept next to "infos.json"
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModProfile {
+    pub m_name: String,
+    pub m_mods: ModList,
+    // false if the save had no "mod_settings.bin" when the profile was captured
+    pub m_has_settings: bool,
+}
+
+impl std::fmt::Display for ModProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\t\t{}", self.m_name, self.m_mods)
+    }
+}
@@ -8,4 +8,8 @@ pub trait OutputManager {
     fn debug(&self, msg: String);
     // fn getline(&self, input_msg: String) -> NSResult<String>;
     fn confirm(&self, msg: String) -> NSResult<bool>;
+    // false for frontends whose `confirm` agrees without asking, so answers aren't remembered
+    fn can_confirm(&self) -> bool {
+        true
+    }
 }
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+// a path the manager relies on and why it was chosen, listed by the path diagnostics
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PathDecision {
+    pub m_item: String,
+    // None if nothing usable was found
+    pub m_path: Option<PathBuf>,
+    pub m_reason: String,
+}
+
+impl PathDecision {
+    #[inline]
+    pub fn new(item: impl ToString, path: Option<PathBuf>, reason: impl ToString) -> Self {
+        Self {
+            m_item: item.to_string(),
+            m_path: path,
+            m_reason: reason.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for PathDecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.m_path {
+            Some(path) => write!(f, "{}: {}", self.m_item, path.display())?,
+            None => write!(f, "{}: {}", self.m_item, t!("path_not_found"))?,
+        }
+        write!(f, "\n\t{}", self.m_reason)
+    }
+}
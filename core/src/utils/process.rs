@@ -0,0 +1,60 @@
+use super::error::*;
+
+const NOITA_PROCESS_NAME: &str = "noita.exe";
+
+#[cfg(target_os = "linux")]
+pub fn is_noita_running() -> NSBoolResult {
+    use std::fs;
+
+    let is_noita = |name: &str| {
+        name.rsplit(['/', '\\'])
+            .next()
+            .is_some_and(|exe| exe.eq_ignore_ascii_case(NOITA_PROCESS_NAME))
+    };
+
+    for entry in fs::read_dir("/proc").explain(&t!("fail_query_process_list"))? {
+        let Ok(entry) = entry else { continue };
+        if !entry
+            .file_name()
+            .to_str()
+            .is_some_and(|pid| pid.bytes().all(|b| b.is_ascii_digit()))
+        {
+            continue;
+        }
+        // processes may exit while being scanned, so read errors are ignored
+        if let Ok(comm) = fs::read_to_string(entry.path().join("comm"))
+            && is_noita(comm.trim())
+        {
+            return Ok(true);
+        }
+        // only argv[0] is checked: the proton launcher carries noita.exe as an argument
+        if let Ok(cmdline) = fs::read(entry.path().join("cmdline"))
+            && let Some(argv0) = cmdline.split(|&b| b == 0).next()
+            && is_noita(&String::from_utf8_lossy(argv0))
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+#[cfg(target_os = "windows")]
+pub fn is_noita_running() -> NSBoolResult {
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    let output = Command::new("tasklist")
+        .args([
+            "/FI",
+            &format!("IMAGENAME eq {NOITA_PROCESS_NAME}"),
+            "/NH",
+            "/FO",
+            "CSV",
+        ])
+        .creation_flags(0x08000000) // don't pop up a console window
+        .output()
+        .explain(&t!("fail_query_process_list"))?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .to_lowercase()
+        .contains(NOITA_PROCESS_NAME))
+}
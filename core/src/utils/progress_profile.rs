@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+use super::game_state::progress::ProgressSummary;
+
+// a named copy of "save00/persistent", the files themselves are kept next to "infos.json"
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProgressProfile {
+    pub m_name: String,
+    pub m_timestamp: i64,
+    // taken when the profile was captured
+    pub m_summary: ProgressSummary,
+}
+
+impl std::fmt::Display for ProgressProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\t\t{}", self.m_name, self.m_summary)
+    }
+}
@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+// folder of "save00" holding the unlocked spells and perks, flags and bones wands,
+// everything else belongs to the current run
+pub const PROGRESS_FOLDER: &str = "persistent";
+
+// which part of a snapshot replaces the live save when loading it
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RestoreMode {
+    #[default]
+    Full,
+    // the world and the player, unlock progress is kept
+    RunOnly,
+    // only "persistent/", the current run is kept
+    ProgressOnly,
+}
+
+// files a restore would touch, paths are relative to "save00" and use '/'
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RestorePlan {
+    pub m_mode: RestoreMode,
+    // copied from the snapshot, overwriting the live file if there is one
+    pub m_replaced: Vec<String>,
+    // live files of the restored set the snapshot doesn't have
+    pub m_removed: Vec<String>,
+}
+
+impl RestoreMode {
+    // e.g. "run-only" from the command line
+    pub fn parse(mode: &str) -> Option<Self> {
+        match mode {
+            "full" => Some(RestoreMode::Full),
+            "run" | "run-only" | "run_only" => Some(RestoreMode::RunOnly),
+            "progress" | "progress-only" | "progress_only" => Some(RestoreMode::ProgressOnly),
+            _ => None,
+        }
+    }
+
+    // whether an entry directly under "save00" is part of this mode's file set
+    pub fn includes_entry(&self, name: &str) -> bool {
+        match self {
+            RestoreMode::Full => true,
+            RestoreMode::RunOnly => name != PROGRESS_FOLDER,
+            RestoreMode::ProgressOnly => name == PROGRESS_FOLDER,
+        }
+    }
+
+    // like `includes_entry` for a path relative to "save00"
+    pub fn includes(&self, relative: &str) -> bool {
+        Path::new(relative)
+            .components()
+            .next()
+            .is_some_and(|first| self.includes_entry(&first.as_os_str().to_string_lossy()))
+    }
+}
+
+impl std::fmt::Display for RestoreMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mode = match self {
+            RestoreMode::Full => t!("restore_mode_full"),
+            RestoreMode::RunOnly => t!("restore_mode_run_only"),
+            RestoreMode::ProgressOnly => t!("restore_mode_progress_only"),
+        };
+        write!(f, "{}", mode)
+    }
+}
+
+impl std::fmt::Display for RestorePlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}: {}", t!("restore_mode"), self.m_mode)?;
+        writeln!(
+            f,
+            "{} ({}):",
+            t!("restore_replaced_files"),
+            self.m_replaced.len()
+        )?;
+        for path in self.m_replaced.iter() {
+            writeln!(f, "\t{}", path)?;
+        }
+        if !self.m_removed.is_empty() {
+            writeln!(
+                f,
+                "{} ({}):",
+                t!("restore_removed_files"),
+                self.m_removed.len()
+            )?;
+            for path in self.m_removed.iter() {
+                writeln!(f, "\t{}", path)?;
+            }
+        }
+        Ok(())
+    }
+}
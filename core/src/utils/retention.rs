@@ -0,0 +1,192 @@
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+// generation based retention, a save is kept if any of the rules wants it, locked saves are always
+// kept and the rules only look at the unlocked ones, so locking a save never costs a generation
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    // the newest n unlocked saves
+    pub keep_last: usize,
+    // the newest save of every hour within the last n hours
+    pub hourly_hours: u32,
+    // the newest save of every day within the last n days
+    pub daily_days: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetentionCandidate {
+    pub index: usize,
+    pub timestamp: i64,
+    pub is_locked: bool,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            keep_last: 10,
+            hourly_hours: 24,
+            daily_days: 7,
+        }
+    }
+}
+
+impl RetentionPolicy {
+    // returns the indexes (ascending) of the candidates that none of the rules keeps
+    pub fn expired(&self, candidates: &[RetentionCandidate], now: i64) -> Vec<usize> {
+        let mut newest_first: Vec<RetentionCandidate> = candidates
+            .iter()
+            .filter(|item| !item.is_locked)
+            .copied()
+            .collect();
+        newest_first.sort_by_key(|item| Reverse(item.timestamp));
+
+        let mut kept: HashSet<usize> = newest_first
+            .iter()
+            .take(self.keep_last)
+            .map(|item| item.index)
+            .collect();
+        Self::keep_per_bucket(
+            &newest_first,
+            now - self.hourly_hours as i64 * 3600,
+            "%Y%m%d%H",
+            &mut kept,
+        );
+        Self::keep_per_bucket(
+            &newest_first,
+            now - self.daily_days as i64 * 86400,
+            "%Y%m%d",
+            &mut kept,
+        );
+
+        let mut expired: Vec<usize> = newest_first
+            .iter()
+            .map(|item| item.index)
+            .filter(|index| !kept.contains(index))
+            .collect();
+        expired.sort_unstable();
+        expired
+    }
+
+    // keeps the newest save of every bucket (local time formatted with `bucket_format`) after `since`
+    fn keep_per_bucket(
+        newest_first: &[RetentionCandidate],
+        since: i64,
+        bucket_format: &str,
+        kept: &mut HashSet<usize>,
+    ) {
+        let mut seen_buckets = HashSet::new();
+        for item in newest_first.iter().filter(|item| item.timestamp > since) {
+            let Some(time) = Local.timestamp_opt(item.timestamp, 0).earliest() else {
+                continue;
+            };
+            if seen_buckets.insert(time.format(bucket_format).to_string()) {
+                kept.insert(item.index);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 3600;
+    const DAY: i64 = 24 * HOUR;
+
+    // a fixed local noon so the hour and day buckets don't depend on when the tests run
+    fn noon() -> i64 {
+        Local
+            .with_ymd_and_hms(2026, 6, 15, 12, 0, 0)
+            .earliest()
+            .unwrap()
+            .timestamp()
+    }
+
+    fn candidate(index: usize, timestamp: i64) -> RetentionCandidate {
+        RetentionCandidate {
+            index,
+            timestamp,
+            is_locked: false,
+        }
+    }
+
+    fn policy(keep_last: usize, hourly_hours: u32, daily_days: u32) -> RetentionPolicy {
+        RetentionPolicy {
+            keep_last,
+            hourly_hours,
+            daily_days,
+        }
+    }
+
+    #[test]
+    fn empty_input() {
+        assert!(RetentionPolicy::default().expired(&[], noon()).is_empty());
+    }
+
+    #[test]
+    fn keep_last_keeps_the_newest() {
+        let now = noon();
+        // out of order on purpose, index 1 is the newest
+        let candidates = [
+            candidate(0, now - 3 * DAY),
+            candidate(1, now - 10),
+            candidate(2, now - 2 * DAY),
+            candidate(3, now - 4 * DAY),
+        ];
+        assert_eq!(policy(2, 0, 0).expired(&candidates, now), vec![0, 3]);
+        assert!(policy(4, 0, 0).expired(&candidates, now).is_empty());
+        assert_eq!(policy(0, 0, 0).expired(&candidates, now), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn hourly_keeps_the_newest_of_each_hour() {
+        let now = noon();
+        let candidates = [
+            // 10:xx
+            candidate(0, now - 2 * HOUR + 60),
+            candidate(1, now - 2 * HOUR + 600),
+            // 11:xx
+            candidate(2, now - HOUR + 60),
+            candidate(3, now - HOUR + 1200),
+            // outside the 3 hour window
+            candidate(4, now - 5 * HOUR),
+        ];
+        assert_eq!(policy(0, 3, 0).expired(&candidates, now), vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn daily_keeps_the_newest_of_each_day() {
+        let now = noon();
+        let candidates = [
+            candidate(0, now - DAY - HOUR),
+            candidate(1, now - DAY + HOUR),
+            candidate(2, now - 2 * DAY),
+            // outside the 3 day window
+            candidate(3, now - 10 * DAY),
+            candidate(4, now - HOUR),
+        ];
+        assert_eq!(policy(0, 0, 3).expired(&candidates, now), vec![0, 3]);
+    }
+
+    #[test]
+    fn locked_saves_are_kept_and_not_counted() {
+        let now = noon();
+        let mut candidates = [
+            candidate(0, now - 4 * DAY),
+            candidate(1, now - 3 * DAY),
+            candidate(2, now - 2 * DAY),
+            candidate(3, now - DAY),
+        ];
+        // the two newest are locked, keep_last still keeps two unlocked ones
+        candidates[2].is_locked = true;
+        candidates[3].is_locked = true;
+        assert!(policy(2, 0, 0).expired(&candidates, now).is_empty());
+
+        candidates[0].is_locked = true;
+        candidates[2].is_locked = false;
+        assert_eq!(policy(1, 0, 0).expired(&candidates, now), vec![1]);
+    }
+}
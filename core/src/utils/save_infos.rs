@@ -1,8 +1,35 @@
 use super::error::*;
+use super::game_state::mods::ModList;
+use super::game_state::perks::PerkSummary;
+use super::game_state::player::PlayerStats;
+use super::game_state::world::WorldProgress;
+use super::mod_profile::ModProfile;
+use super::progress_profile::ProgressProfile;
+use super::settings::Settings;
+use super::snapshot_filter::SnapshotFilter;
 
+use chrono::{Local, NaiveDateTime, TimeZone};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
+// version of the layout of "infos.json", bumped whenever a migration is needed
+const INFO_VERSION: u32 = 2;
+
+// every "date_format" and "time_format" that has ever been written into "infos.json"
+const LEGACY_DATE_FORMATS: [&str; 3] = ["%Y年%m月%d日", "%Y-%m-%d", "%d/%m/%Y"];
+const LEGACY_TIME_FORMATS: [&str; 4] = ["%H时%M分%S秒", "%H時%M分%S秒", "%I:%M:%S %p", "%H:%M:%S"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SaveKind {
+    #[default]
+    Manual,
+    Quick,
+    Auto,
+    PreLoad,
+    Imported,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SingleSave {
     pub m_date: String,
@@ -10,25 +37,147 @@ pub struct SingleSave {
     pub m_name: String,
     pub m_note: String,
     m_islocked: bool,
+    #[serde(default)]
+    m_kind: SaveKind,
+    // unix timestamp, `m_date` and `m_time` are localized and only meant for display
+    #[serde(default)]
+    m_timestamp: i64,
+    // read from the snapshot's "player.xml", None if it couldn't be parsed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    m_player: Option<PlayerStats>,
+    // read from the snapshot's "world_state.xml" and "player.xml"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    m_perks: Option<PerkSummary>,
+    // read from the snapshot's "world_state.xml"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    m_world: Option<WorldProgress>,
+    // seed of the run the snapshot belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    m_seed: Option<u32>,
+    // version marker of the Noita install the snapshot was taken with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    m_game_build: Option<String>,
+    // read from the snapshot's "mod_config.xml"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    m_mods: Option<ModList>,
+    // "save_shared" was snapshotted alongside the slot
+    #[serde(default)]
+    m_has_shared_settings: bool,
+    // the include and exclude rules the snapshot was taken with, None if it holds every file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    m_filter: Option<SnapshotFilter>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AllInfos {
+    #[serde(default)]
+    info_version: u32,
     noita_exe_path: PathBuf,
+    #[serde(default)]
+    settings: Settings,
+    #[serde(default)]
+    mod_profiles: Vec<ModProfile>,
+    #[serde(default)]
+    progress_profiles: Vec<ProgressProfile>,
     pub saves: Vec<SingleSave>,
 }
 
+impl SaveKind {
+    // saves made before `m_kind` existed can only be told apart by their name prefix
+    fn from_legacy_name(name: &str) -> Self {
+        if name.starts_with("as_") {
+            SaveKind::Auto
+        } else if name.starts_with("qs_") {
+            SaveKind::Quick
+        } else {
+            SaveKind::Manual
+        }
+    }
+}
+
 impl SingleSave {
-    pub fn new(date: String, time: String, name: String, note: String) -> Self {
+    pub fn new(
+        date: String,
+        time: String,
+        timestamp: i64,
+        name: String,
+        note: String,
+        kind: SaveKind,
+    ) -> Self {
         Self {
             m_date: date,
             m_time: time,
             m_name: name,
             m_note: note,
             m_islocked: false,
+            m_kind: kind,
+            m_timestamp: timestamp,
+            m_player: None,
+            m_perks: None,
+            m_world: None,
+            m_seed: None,
+            m_game_build: None,
+            m_mods: None,
+            m_has_shared_settings: false,
+            m_filter: None,
         }
     }
 
+    fn parse_legacy_timestamp(date: &str, time: &str) -> Option<i64> {
+        let datetime = format!("{date} {time}");
+        LEGACY_DATE_FORMATS
+            .iter()
+            .flat_map(|d| LEGACY_TIME_FORMATS.iter().map(move |t| format!("{d} {t}")))
+            .find_map(|format| NaiveDateTime::parse_from_str(&datetime, &format).ok())
+            .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+            .map(|local| local.timestamp())
+    }
+
+    #[inline]
+    pub fn modify_player(&mut self, new_player: Option<PlayerStats>) {
+        self.m_player = new_player;
+    }
+
+    #[inline]
+    pub fn modify_perks(&mut self, new_perks: Option<PerkSummary>) {
+        self.m_perks = new_perks;
+    }
+
+    #[inline]
+    pub fn modify_world(&mut self, new_world: Option<WorldProgress>) {
+        self.m_world = new_world;
+    }
+
+    #[inline]
+    pub fn modify_seed(&mut self, new_seed: Option<u32>) {
+        self.m_seed = new_seed;
+    }
+
+    #[inline]
+    pub fn modify_game_build(&mut self, new_game_build: Option<String>) {
+        self.m_game_build = new_game_build;
+    }
+
+    #[inline]
+    pub fn modify_mods(&mut self, new_mods: Option<ModList>) {
+        self.m_mods = new_mods;
+    }
+
+    #[inline]
+    pub fn modify_has_shared_settings(&mut self, has_shared_settings: bool) {
+        self.m_has_shared_settings = has_shared_settings;
+    }
+
+    #[inline]
+    pub fn modify_filter(&mut self, new_filter: Option<SnapshotFilter>) {
+        self.m_filter = new_filter;
+    }
+
+    #[inline]
+    pub fn modify_timestamp(&mut self, new_timestamp: i64) {
+        self.m_timestamp = new_timestamp;
+    }
+
     #[inline]
     pub fn modify_date(&mut self, new_date: String) {
         self.m_date = new_date;
@@ -84,6 +233,56 @@ impl SingleSave {
         self.m_islocked
     }
 
+    #[inline]
+    pub fn get_kind(&self) -> SaveKind {
+        self.m_kind
+    }
+
+    #[inline]
+    pub fn get_timestamp(&self) -> i64 {
+        self.m_timestamp
+    }
+
+    #[inline]
+    pub fn get_player(&self) -> Option<&PlayerStats> {
+        self.m_player.as_ref()
+    }
+
+    #[inline]
+    pub fn get_perks(&self) -> Option<&PerkSummary> {
+        self.m_perks.as_ref()
+    }
+
+    #[inline]
+    pub fn get_world(&self) -> Option<&WorldProgress> {
+        self.m_world.as_ref()
+    }
+
+    #[inline]
+    pub fn get_seed(&self) -> Option<u32> {
+        self.m_seed
+    }
+
+    #[inline]
+    pub fn get_game_build(&self) -> Option<&str> {
+        self.m_game_build.as_deref()
+    }
+
+    #[inline]
+    pub fn get_mods(&self) -> Option<&ModList> {
+        self.m_mods.as_ref()
+    }
+
+    #[inline]
+    pub fn has_shared_settings(&self) -> bool {
+        self.m_has_shared_settings
+    }
+
+    #[inline]
+    pub fn get_filter(&self) -> Option<&SnapshotFilter> {
+        self.m_filter.as_ref()
+    }
+
     #[inline]
     pub fn protect(&self) -> NSComResult {
         if self.m_islocked {
@@ -94,6 +293,7 @@ impl SingleSave {
     }
 }
 
+// "{:#}" additionally prints the snapshot's details on the following lines
 impl std::fmt::Display for SingleSave {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -103,18 +303,274 @@ impl std::fmt::Display for SingleSave {
             self.get_time(),
             self.get_name(),
             self.get_note()
-        )
+        )?;
+        if let Some(player) = &self.m_player {
+            if f.alternate() {
+                write!(f, "\n\t{:#}", player)?;
+            } else {
+                write!(f, "\t\t{}", player)?;
+            }
+        }
+        if let Some(world) = &self.m_world {
+            if f.alternate() {
+                write!(f, "\n\t{:#}", world)?;
+            } else {
+                write!(f, "\t{}", world)?;
+            }
+        }
+        if let Some(perks) = &self.m_perks
+            && f.alternate()
+        {
+            write!(f, "\n\t{:#}", perks)?;
+        }
+        if let Some(seed) = self.m_seed
+            && f.alternate()
+        {
+            write!(f, "\n\t{}: {}", t!("run_seed"), seed)?;
+        }
+        if let Some(build) = &self.m_game_build
+            && f.alternate()
+        {
+            write!(f, "\n\t{}: {}", t!("game_build"), build)?;
+        }
+        if let Some(mods) = &self.m_mods
+            && f.alternate()
+        {
+            write!(f, "\n\t{}", mods)?;
+        }
+        if self.m_has_shared_settings && f.alternate() {
+            write!(f, "\n\t{}", t!("shared_settings_included"))?;
+        }
+        if let Some(filter) = &self.m_filter
+            && f.alternate()
+        {
+            write!(f, "\n\t{}", filter)?;
+        }
+        Ok(())
     }
 }
 
 impl AllInfos {
+    // returns true if anything has been changed and the info file should be rewritten
+    pub fn migrate(&mut self) -> bool {
+        if self.info_version >= INFO_VERSION {
+            return false;
+        }
+        if self.info_version < 1 {
+            for save in self.saves.iter_mut() {
+                save.m_kind = SaveKind::from_legacy_name(&save.m_name);
+            }
+        }
+        if self.info_version < 2 {
+            // saves whose date can't be parsed are treated as the oldest ones
+            for save in self.saves.iter_mut() {
+                save.m_timestamp =
+                    SingleSave::parse_legacy_timestamp(&save.m_date, &save.m_time).unwrap_or(0);
+            }
+        }
+        self.info_version = INFO_VERSION;
+        true
+    }
+
     #[inline]
     pub fn get_exe_path(&self) -> &Path {
         &self.noita_exe_path
     }
 
+    #[inline]
+    pub fn get_settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    #[inline]
+    pub fn get_settings_mut(&mut self) -> &mut Settings {
+        &mut self.settings
+    }
+
+    #[inline]
+    pub fn get_mod_profiles(&self) -> &[ModProfile] {
+        &self.mod_profiles
+    }
+
+    #[inline]
+    pub fn get_mod_profiles_mut(&mut self) -> &mut Vec<ModProfile> {
+        &mut self.mod_profiles
+    }
+
+    #[inline]
+    pub fn get_progress_profiles(&self) -> &[ProgressProfile] {
+        &self.progress_profiles
+    }
+
+    #[inline]
+    pub fn get_progress_profiles_mut(&mut self) -> &mut Vec<ProgressProfile> {
+        &mut self.progress_profiles
+    }
+
+    // the saves the storage budget may evict, oldest first: unlocked auto and quick saves except
+    // the newest save of all
+    pub fn storage_eviction_order(&self) -> Vec<String> {
+        let newest = self.saves.len().saturating_sub(1);
+        let mut evictable: Vec<(i64, String)> = self
+            .saves
+            .iter()
+            .enumerate()
+            .filter(|&(index, item)| {
+                index != newest
+                    && !item.is_locked()
+                    && matches!(item.get_kind(), SaveKind::Auto | SaveKind::Quick)
+            })
+            .map(|(_, item)| (item.get_timestamp(), item.get_name().to_string()))
+            .collect();
+        evictable.sort();
+        evictable.into_iter().map(|(_, name)| name).collect()
+    }
+
     #[inline]
     pub fn set_noita_path(&mut self, new_path: String) {
         self.noita_exe_path = PathBuf::from(new_path);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local_timestamp(datetime: &str) -> i64 {
+        let naive = NaiveDateTime::parse_from_str(datetime, "%Y-%m-%d %H:%M:%S").unwrap();
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .unwrap()
+            .timestamp()
+    }
+
+    #[test]
+    fn migrates_v0_kinds_and_timestamps() {
+        // written before "info_version", "m_kind" and "m_timestamp" existed
+        let mut infos: AllInfos = serde_json::from_str(
+            r#"{
+                "noita_exe_path": "",
+                "saves": [
+                    {"m_date": "2025年09月20日", "m_time": "20时58分40秒", "m_name": "as_zjuu6O",
+                     "m_note": "", "m_islocked": true},
+                    {"m_date": "2025-09-21", "m_time": "08:05:00 PM", "m_name": "qs_AkthKU",
+                     "m_note": "", "m_islocked": false},
+                    {"m_date": "21/09/2025", "m_time": "21:00:01", "m_name": "boss fight",
+                     "m_note": "note", "m_islocked": false},
+                    {"m_date": "someday", "m_time": "noon", "m_name": "as_broken",
+                     "m_note": "", "m_islocked": false}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert!(infos.migrate());
+        assert_eq!(infos.info_version, INFO_VERSION);
+
+        let kinds: Vec<SaveKind> = infos.saves.iter().map(SingleSave::get_kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                SaveKind::Auto,
+                SaveKind::Quick,
+                SaveKind::Manual,
+                SaveKind::Auto
+            ]
+        );
+        let timestamps: Vec<i64> = infos.saves.iter().map(SingleSave::get_timestamp).collect();
+        assert_eq!(
+            timestamps,
+            [
+                local_timestamp("2025-09-20 20:58:40"),
+                local_timestamp("2025-09-21 20:05:00"),
+                local_timestamp("2025-09-21 21:00:01"),
+                // unparsable dates sort as the oldest saves
+                0
+            ]
+        );
+        assert!(infos.saves[0].is_locked());
+        assert!(!infos.migrate());
+    }
+
+    #[test]
+    fn migrates_v1_timestamps_only() {
+        // v1 already has "m_kind", a renamed save keeps the kind it was given
+        let mut infos: AllInfos = serde_json::from_str(
+            r#"{
+                "info_version": 1,
+                "noita_exe_path": "",
+                "settings": {"compare_hashes": true},
+                "saves": [
+                    {"m_date": "2025-09-20", "m_time": "20:58:40", "m_name": "as_renamed",
+                     "m_note": "", "m_islocked": false, "m_kind": "manual"},
+                    {"m_date": "2025-09-20", "m_time": "25:61:00", "m_name": "x",
+                     "m_note": "", "m_islocked": false, "m_kind": "pre_load"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert!(infos.migrate());
+        assert_eq!(infos.saves[0].get_kind(), SaveKind::Manual);
+        assert_eq!(infos.saves[1].get_kind(), SaveKind::PreLoad);
+        assert_eq!(
+            infos.saves[0].get_timestamp(),
+            local_timestamp("2025-09-20 20:58:40")
+        );
+        assert_eq!(infos.saves[1].get_timestamp(), 0);
+        assert!(infos.get_settings().compare_hashes);
+    }
+
+    #[test]
+    fn current_version_is_left_alone() {
+        let mut infos: AllInfos = serde_json::from_str(
+            r#"{
+                "info_version": 2,
+                "noita_exe_path": "",
+                "saves": [
+                    {"m_date": "?", "m_time": "?", "m_name": "as_x", "m_note": "",
+                     "m_islocked": false, "m_kind": "quick", "m_timestamp": 42}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert!(!infos.migrate());
+        assert_eq!(infos.saves[0].get_kind(), SaveKind::Quick);
+        assert_eq!(infos.saves[0].get_timestamp(), 42);
+    }
+
+    #[test]
+    fn storage_evicts_oldest_unlocked_auto_and_quick_first() {
+        let mut infos: AllInfos =
+            serde_json::from_str(r#"{"info_version": 2, "noita_exe_path": "", "saves": []}"#)
+                .unwrap();
+        let save = |name: &str, timestamp: i64, kind: SaveKind| {
+            SingleSave::new(
+                String::new(),
+                String::new(),
+                timestamp,
+                name.to_string(),
+                String::new(),
+                kind,
+            )
+        };
+        let mut locked = save("as_locked", 10, SaveKind::Auto);
+        locked.lock();
+        infos.saves = vec![
+            save("as_new", 50, SaveKind::Auto),
+            locked,
+            save("manual", 5, SaveKind::Manual),
+            save("qs_old", 20, SaveKind::Quick),
+            save("pre_load", 1, SaveKind::PreLoad),
+            save("as_old", 30, SaveKind::Auto),
+            // the newest save is kept even though it is the oldest by timestamp
+            save("as_newest", 0, SaveKind::Auto),
+        ];
+        assert_eq!(
+            infos.storage_eviction_order(),
+            vec!["qs_old", "as_old", "as_new"]
+        );
+
+        infos.saves.clear();
+        assert!(infos.storage_eviction_order().is_empty());
+    }
+}
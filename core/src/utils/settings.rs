@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::error::*;
+use super::launcher::{LaunchMode, LauncherKind};
+use super::retention::RetentionPolicy;
+use super::snapshot_filter::SnapshotFilter;
+
+// user adjustable options, stored in "infos.json" next to the save list
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Settings {
+    // also hash file contents when recording a snapshot's manifest,
+    // slower but catches changes that keep both size and mtime
+    pub compare_hashes: bool,
+    // pause timed auto save while Noita isn't running
+    pub auto_save_only_in_game: bool,
+    // seconds to wait after Noita exits before taking the last auto save
+    pub final_save_delay_secs: u64,
+    // used by `prune`, manual saves are never pruned
+    pub auto_save_retention: RetentionPolicy,
+    pub quick_save_retention: RetentionPolicy,
+    // 0 means unlimited, otherwise old unlocked auto and quick saves are evicted after every save,
+    // only the snapshots themselves count toward it
+    pub storage_budget_gb: f64,
+    // also snapshot Noita's options and keybindings in "save_shared" with every save
+    pub snapshot_shared_settings: bool,
+    // which files of "save00" new snapshots copy, e.g. leaving out "stats" or logs
+    pub snapshot_filter: SnapshotFilter,
+    // what "startgame" does unless a mode is given for that launch
+    pub launch_mode: LaunchMode,
+    // Linux only, how Noita is started and so where its live save is
+    pub launcher: LauncherKind,
+    // Linux only, the Proton folder or its "proton" script, empty means the one Steam would pick
+    pub proton_path: String,
+    // Linux only, the Steam folder to use, empty means the one holding Noita
+    pub steam_root: String,
+    // Linux only, used with the "wine" launcher, the prefix and the "noita.exe" inside it
+    pub wine_prefix: String,
+    pub wine_exe: String,
+    // empty means "wine" from PATH, e.g. a Lutris runner's "bin/wine" otherwise
+    pub wine_binary: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            compare_hashes: false,
+            auto_save_only_in_game: false,
+            final_save_delay_secs: 10,
+            auto_save_retention: RetentionPolicy::default(),
+            quick_save_retention: RetentionPolicy::default(),
+            storage_budget_gb: 0.0,
+            snapshot_shared_settings: false,
+            snapshot_filter: SnapshotFilter::default(),
+            launch_mode: LaunchMode::default(),
+            launcher: LauncherKind::default(),
+            proton_path: String::new(),
+            steam_root: String::new(),
+            wine_prefix: String::new(),
+            wine_exe: String::new(),
+            wine_binary: String::new(),
+        }
+    }
+}
+
+impl Settings {
+    // every setting as ("key", "value in json")
+    pub fn entries(&self) -> NSResult<Vec<(String, String)>> {
+        let Value::Object(map) = serde_json::to_value(self)? else {
+            return Ok(Vec::new());
+        };
+        Ok(map
+            .into_iter()
+            .map(|(key, value)| (key, value.to_string()))
+            .collect())
+    }
+
+    // `value` is parsed as json first, anything that isn't valid json is taken as a plain string
+    pub fn set(&mut self, key: &str, value: &str) -> NSComResult {
+        let mut map = serde_json::to_value(&*self)?;
+        let Some(field) = map.get_mut(key) else {
+            return throw(&t!("unknown_setting", key = key));
+        };
+        *field = serde_json::from_str(value).unwrap_or(Value::String(value.to_string()));
+        let settings: Settings = serde_json::from_value(map).explain(&t!(
+            "invalid_setting_value",
+            key = key,
+            value = value
+        ))?;
+        // a broken pattern would otherwise only show up with the next save
+        settings.snapshot_filter.compile().explain(&t!(
+            "invalid_setting_value",
+            key = key,
+            value = value
+        ))?;
+        *self = settings;
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+
+use super::error::*;
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+// glob rules for the files of "save00" a snapshot copies, like in ".gitignore" a pattern without
+// '/' matches any file or folder name, otherwise the path relative to "save00"
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct SnapshotFilter {
+    // empty means every file
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+// a compiled `SnapshotFilter`, the default one keeps every file
+#[derive(Debug, Default)]
+pub struct PathFilter {
+    m_include: Vec<(Pattern, bool)>,
+    m_exclude: Vec<(Pattern, bool)>,
+}
+
+impl SnapshotFilter {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    pub fn compile(&self) -> NSResult<PathFilter> {
+        let compile = |patterns: &[String]| -> NSResult<Vec<(Pattern, bool)>> {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    Ok((
+                        Pattern::new(pattern.trim_matches('/'))
+                            .explain(&t!("invalid_snapshot_pattern", pattern = pattern))?,
+                        pattern.contains('/'),
+                    ))
+                })
+                .collect()
+        };
+        Ok(PathFilter {
+            m_include: compile(&self.include)?,
+            m_exclude: compile(&self.exclude)?,
+        })
+    }
+}
+
+impl PathFilter {
+    #[inline]
+    pub fn keeps_everything(&self) -> bool {
+        self.m_include.is_empty() && self.m_exclude.is_empty()
+    }
+
+    // `relative` is a file's path relative to "save00" using '/', a pattern matching one of
+    // its parent folders applies to it as well
+    pub fn keeps(&self, relative: &str) -> bool {
+        let matches = |patterns: &[(Pattern, bool)]| {
+            let mut prefix_end = 0;
+            relative.split('/').any(|name| {
+                prefix_end += name.len();
+                let prefix = &relative[..prefix_end];
+                prefix_end += 1;
+                patterns.iter().any(|(pattern, is_path)| {
+                    pattern.matches_with(if *is_path { prefix } else { name }, MATCH_OPTIONS)
+                })
+            })
+        };
+        (self.m_include.is_empty() || matches(&self.m_include)) && !matches(&self.m_exclude)
+    }
+}
+
+impl std::fmt::Display for SnapshotFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if !self.include.is_empty() {
+            parts.push(format!(
+                "{}: {}",
+                t!("snapshot_filter_include"),
+                self.include.join(" ")
+            ));
+        }
+        if !self.exclude.is_empty() {
+            parts.push(format!(
+                "{}: {}",
+                t!("snapshot_filter_exclude"),
+                self.exclude.join(" ")
+            ));
+        }
+        write!(f, "{}", parts.join("  "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SnapshotFilter;
+
+    fn filter(include: &[&str], exclude: &[&str]) -> super::PathFilter {
+        SnapshotFilter {
+            include: include.iter().map(|item| item.to_string()).collect(),
+            exclude: exclude.iter().map(|item| item.to_string()).collect(),
+        }
+        .compile()
+        .unwrap()
+    }
+
+    #[test]
+    fn default_keeps_everything() {
+        let filter = filter(&[], &[]);
+        assert!(filter.keeps_everything());
+        assert!(filter.keeps("world_state.xml"));
+        assert!(filter.keeps("world/world_0_0.png_petri"));
+    }
+
+    #[test]
+    fn include_only() {
+        let filter = filter(&["world", "*.xml"], &[]);
+        assert!(filter.keeps("world_state.xml"));
+        assert!(filter.keeps("world/world_0_0.png_petri"));
+        assert!(!filter.keeps("stats/_stats.salakieli"));
+    }
+
+    #[test]
+    fn exclude_only() {
+        let filter = filter(&[], &["stats", "*.log"]);
+        assert!(filter.keeps("world_state.xml"));
+        assert!(!filter.keeps("stats/sessions/20260101-101010_stats.xml"));
+        assert!(!filter.keeps("logger.log"));
+        assert!(!filter.keeps("world/debug.log"));
+    }
+
+    #[test]
+    fn path_rules_match_from_the_save_root() {
+        let filter = filter(&[], &["stats/sessions/", "world/*.bin"]);
+        assert!(!filter.keeps("stats/sessions/20260101-101010_stats.xml"));
+        assert!(filter.keeps("stats/_stats.salakieli"));
+        assert!(!filter.keeps("world/area.bin"));
+        // '*' doesn't cross folders in a path rule
+        assert!(filter.keeps("world/sub/area.bin"));
+        assert!(filter.keeps("persistent/stats/sessions/x.xml"));
+    }
+
+    #[test]
+    fn name_rules_match_nested_names() {
+        let filter = filter(&[], &["sessions"]);
+        assert!(!filter.keeps("stats/sessions/20260101-101010_stats.xml"));
+        assert!(!filter.keeps("a/b/sessions"));
+        assert!(filter.keeps("stats/sessions_old.xml"));
+    }
+
+    #[test]
+    fn exclude_wins_over_include() {
+        let filter = filter(&["world"], &["*.bin"]);
+        assert!(filter.keeps("world/world_0_0.png_petri"));
+        assert!(!filter.keeps("world/area.bin"));
+        assert!(!filter.keeps("player.xml"));
+    }
+}
@@ -0,0 +1,502 @@
+use directories::BaseDirs;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::error::*;
+use super::launcher::{Launcher, LauncherKind};
+use super::path_decision::PathDecision;
+use super::vdf::Vdf;
+
+pub const APPID: &str = "881100";
+// where Noita keeps "save00" inside its Proton prefix
+const PREFIX_SAVE_PATH: &str =
+    "pfx/drive_c/users/steamuser/AppData/LocalLow/Nolla_Games_Noita/save00";
+const DEFAULT_INSTALL_DIR: &str = "Noita";
+// what Steam runs Windows games with unless told otherwise
+const DEFAULT_COMPAT_TOOL: &str = "proton_experimental";
+const FALLBACK_PROTON_DIR: &str = "Proton - Experimental";
+// the key of the compatibility tool mapping applying to every game without its own entry
+const GLOBAL_MAPPING: &str = "0";
+const SYSTEM_COMPAT_TOOLS_DIR: &str = "/usr/share/steam/compatibilitytools.d";
+
+// every place a Steam client keeps its files, relative to the home directory, in the order
+// they are preferred, "~/.local/share/Steam" is taken from the XDG data directory instead
+const NATIVE_ROOTS: [&str; 2] = [".steam/steam", ".steam/debian-installation"];
+const FLATPAK_ROOTS: [&str; 2] = [
+    ".var/app/com.valvesoftware.Steam/.local/share/Steam",
+    ".var/app/com.valvesoftware.Steam/data/Steam",
+];
+const SNAP_ROOTS: [&str; 1] = ["snap/steam/common/.local/share/Steam"];
+const FLATPAK_APP: &str = "com.valvesoftware.Steam";
+const SNAP_APP: &str = "snap/steam/";
+
+// every Steam path needed to find and start Noita, and the reasons each was chosen
+#[derive(Debug, Clone)]
+pub struct SteamLayout {
+    m_steam_dir: PathBuf,
+    m_noita_dir: PathBuf,
+    m_compat_data: PathBuf,
+    m_proton_dir: PathBuf,
+    // a library lists app 881100 or holds its app manifest
+    m_has_noita: bool,
+    m_decisions: Vec<PathDecision>,
+}
+
+impl SteamLayout {
+    // never fails, anything unreadable falls back to Steam's default layout and is noted in
+    // the decisions, `proton_override` is the "proton_path" setting
+    pub fn detect(steam_dir: PathBuf, root_reason: String, proton_override: &str) -> Self {
+        let mut decisions = vec![PathDecision::new(
+            t!("path_item_steam_root"),
+            Some(steam_dir.clone()),
+            root_reason,
+        )];
+
+        let libraries_vdf = steam_dir.join("steamapps/libraryfolders.vdf");
+        let libraries = Self::libraries(&libraries_vdf);
+        let (library, reason) = match &libraries {
+            Ok(Some(libraries)) => match libraries.iter().find(|(_, has_noita)| *has_noita) {
+                Some((library, _)) => (
+                    library.clone(),
+                    t!(
+                        "library_from_vdf",
+                        appid = APPID,
+                        path = libraries_vdf.display()
+                    ),
+                ),
+                None => (
+                    steam_dir.clone(),
+                    t!(
+                        "library_not_listed",
+                        appid = APPID,
+                        path = libraries_vdf.display()
+                    ),
+                ),
+            },
+            Ok(None) => (
+                steam_dir.clone(),
+                t!("library_vdf_missing", path = libraries_vdf.display()),
+            ),
+            Err(_) => (
+                steam_dir.clone(),
+                t!("steam_file_unreadable", path = libraries_vdf.display()),
+            ),
+        };
+        decisions.push(PathDecision::new(
+            t!("path_item_library"),
+            Some(library.clone()),
+            reason,
+        ));
+
+        let app_manifest = library.join(format!("steamapps/appmanifest_{APPID}.acf"));
+        let has_noita = app_manifest.exists()
+            || matches!(&libraries, Ok(Some(libraries)) if libraries.iter().any(|(_, has_noita)| *has_noita));
+        let install_dir = Vdf::load(&app_manifest)
+            .ok()
+            .flatten()
+            .and_then(|manifest| {
+                manifest
+                    .get_path(&["AppState", "installdir"])
+                    .and_then(Vdf::as_str)
+                    .map(str::to_string)
+            });
+        let reason = match &install_dir {
+            Some(_) => t!("install_dir_from_manifest", path = app_manifest.display()),
+            None => t!(
+                "install_dir_default",
+                path = app_manifest.display(),
+                dir = DEFAULT_INSTALL_DIR
+            ),
+        };
+        let noita_dir = library
+            .join("steamapps/common")
+            .join(install_dir.as_deref().unwrap_or(DEFAULT_INSTALL_DIR));
+        decisions.push(PathDecision::new(
+            t!("path_item_noita"),
+            Some(noita_dir.clone()),
+            reason,
+        ));
+
+        let compat_data = library.join(format!("steamapps/compatdata/{APPID}"));
+        decisions.push(PathDecision::new(
+            t!("path_item_compat_data"),
+            Some(compat_data.clone()),
+            t!("compat_data_in_library"),
+        ));
+        decisions.push(PathDecision::new(
+            t!("path_item_live_save"),
+            Some(compat_data.join(PREFIX_SAVE_PATH)),
+            t!("live_save_in_prefix"),
+        ));
+
+        let library_dirs: Vec<PathBuf> = match libraries {
+            Ok(Some(libraries)) => libraries.into_iter().map(|(path, _)| path).collect(),
+            _ => vec![steam_dir.clone()],
+        };
+        let (proton_dir, reason) = if proton_override.is_empty() {
+            Self::mapped_proton(&steam_dir, &library_dirs)
+        } else {
+            let path = PathBuf::from(proton_override);
+            // either the tool's folder or the "proton" script inside it
+            let dir = if path.ends_with("proton") && path.is_file() {
+                path.parent().map_or(path.clone(), Path::to_path_buf)
+            } else {
+                path
+            };
+            (dir, t!("proton_from_setting").to_string())
+        };
+        let proton_version = Self::read_proton_version(&proton_dir);
+        let proton_exe = proton_dir.join("proton");
+        decisions.push(PathDecision::new(
+            t!("path_item_proton"),
+            proton_exe.exists().then_some(proton_exe),
+            match &proton_version {
+                Some(version) => format!("{reason}\n\t{}: {version}", t!("proton_version")),
+                None => reason,
+            },
+        ));
+
+        let runsh = steam_dir.join("ubuntu12_32/steam-runtime/run.sh");
+        let runtime_reason = if runsh.exists() {
+            t!("steam_runtime_found")
+        } else {
+            t!("steam_runtime_missing")
+        };
+        decisions.push(PathDecision::new(
+            t!("path_item_steam_runtime"),
+            runsh.exists().then_some(runsh),
+            runtime_reason,
+        ));
+
+        Self {
+            m_steam_dir: steam_dir,
+            m_noita_dir: noita_dir,
+            m_compat_data: compat_data,
+            m_proton_dir: proton_dir,
+            m_has_noita: has_noita,
+            m_decisions: decisions,
+        }
+    }
+
+    // the detected layouts of every Steam client installed, native, Flatpak or Snap, with
+    // the name of its kind, an install reachable through several paths is only listed once
+    pub fn detect_installs(proton_override: &str) -> Vec<(Self, &'static str)> {
+        let Some(dirs) = BaseDirs::new() else {
+            return Vec::new();
+        };
+        let home = dirs.home_dir();
+        let roots = std::iter::once((dirs.data_dir().join("Steam"), "native"))
+            .chain(NATIVE_ROOTS.iter().map(|root| (home.join(root), "native")))
+            .chain(
+                FLATPAK_ROOTS
+                    .iter()
+                    .map(|root| (home.join(root), "Flatpak")),
+            )
+            .chain(SNAP_ROOTS.iter().map(|root| (home.join(root), "Snap")));
+        let mut seen = Vec::new();
+        let mut installs = Vec::new();
+        for (root, kind) in roots {
+            // "~/.steam/steam" is usually a link to one of the others
+            let Ok(canonical) = root.canonicalize() else {
+                continue;
+            };
+            if !canonical.join("steamapps").is_dir() || seen.contains(&canonical) {
+                continue;
+            }
+            seen.push(canonical);
+            let reason = t!("steam_root_found", kind = kind).to_string();
+            installs.push((Self::detect(root, reason, proton_override), kind));
+        }
+        installs
+    }
+
+    // replaces why the Steam root was chosen once it's known
+    pub fn with_root_reason(mut self, reason: String) -> Self {
+        if let Some(root) = self.m_decisions.first_mut() {
+            root.m_reason = reason;
+        }
+        self
+    }
+
+    // every library as (path, whether it holds Noita), None if Steam has no library list
+    fn libraries(libraries_vdf: &Path) -> NSResult<Option<Vec<(PathBuf, bool)>>> {
+        let Some(vdf) = Vdf::load(libraries_vdf)? else {
+            return Ok(None);
+        };
+        let Some(folders) = vdf.get("libraryfolders") else {
+            return Ok(Some(Vec::new()));
+        };
+        Ok(Some(
+            folders
+                .entries()
+                .iter()
+                .filter_map(|(_, folder)| {
+                    let path = folder.get("path")?.as_str()?;
+                    let has_noita = folder.get_path(&["apps", APPID]).is_some();
+                    Some((PathBuf::from(path), has_noita))
+                })
+                .collect(),
+        ))
+    }
+
+    // the tool Steam would run Noita with, following the mapping in "config.vdf"
+    fn mapped_proton(steam_dir: &Path, libraries: &[PathBuf]) -> (PathBuf, String) {
+        let config_vdf = steam_dir.join("config/config.vdf");
+        let mapping = Vdf::load(&config_vdf).ok().flatten().and_then(|config| {
+            config
+                .get_path(&[
+                    "InstallConfigStore",
+                    "Software",
+                    "Valve",
+                    "Steam",
+                    "CompatToolMapping",
+                ])
+                .cloned()
+        });
+        let tool_of = |key: &str| {
+            mapping
+                .as_ref()
+                .and_then(|mapping| mapping.get_path(&[key, "name"]))
+                .and_then(Vdf::as_str)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+        };
+        let (tool, reason) = if let Some(tool) = tool_of(APPID) {
+            let reason = t!(
+                "proton_from_app_mapping",
+                tool = tool,
+                path = config_vdf.display()
+            );
+            (tool, reason)
+        } else if let Some(tool) = tool_of(GLOBAL_MAPPING) {
+            let reason = t!(
+                "proton_from_global_mapping",
+                tool = tool,
+                path = config_vdf.display()
+            );
+            (tool, reason)
+        } else {
+            let tool = DEFAULT_COMPAT_TOOL.to_string();
+            let reason = t!("proton_default", tool = tool, path = config_vdf.display());
+            (tool, reason)
+        };
+        match Self::find_compat_tool(&tool, steam_dir, libraries) {
+            Some(dir) => (dir, reason.to_string()),
+            None => (
+                steam_dir.join("steamapps/common").join(FALLBACK_PROTON_DIR),
+                format!(
+                    "{reason}\n\t{}",
+                    t!(
+                        "proton_not_found",
+                        tool = tool,
+                        fallback = FALLBACK_PROTON_DIR
+                    )
+                ),
+            ),
+        }
+    }
+
+    // custom tools like GE-Proton live in "compatibilitytools.d", official ones in a library
+    fn find_compat_tool(tool: &str, steam_dir: &Path, libraries: &[PathBuf]) -> Option<PathBuf> {
+        for root in [
+            steam_dir.join("compatibilitytools.d"),
+            PathBuf::from(SYSTEM_COMPAT_TOOLS_DIR),
+        ] {
+            let Ok(entries) = fs::read_dir(&root) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let dir = entry.path();
+                if entry.file_name() == tool {
+                    return Some(dir);
+                }
+                let declared = Vdf::load(&dir.join("compatibilitytool.vdf"))
+                    .ok()
+                    .flatten()
+                    .and_then(|vdf| {
+                        vdf.get_path(&["compatibilitytools", "compat_tools", tool])
+                            .cloned()
+                    });
+                if let Some(declared) = declared {
+                    let install_path = declared
+                        .get("install_path")
+                        .and_then(Vdf::as_str)
+                        .unwrap_or(".");
+                    return Some(dir.join(install_path));
+                }
+            }
+        }
+        Self::official_tool_dirs(tool)
+            .into_iter()
+            .flat_map(|name| {
+                libraries
+                    .iter()
+                    .map(move |library| library.join("steamapps/common").join(&name))
+            })
+            .find(|dir| dir.join("proton").exists())
+    }
+
+    // e.g. "proton_9" is installed as "Proton 9.0", "proton_513" as "Proton 5.13" and
+    // "proton_10" as "Proton 10.0", there was never a Proton 1.x so a leading 1 starts a
+    // two digit major version
+    fn official_tool_dirs(tool: &str) -> Vec<String> {
+        match tool {
+            "proton_experimental" => vec![FALLBACK_PROTON_DIR.to_string()],
+            "proton_hotfix" => vec!["Proton Hotfix".to_string()],
+            _ => match tool.strip_prefix("proton_") {
+                Some(version)
+                    if !version.is_empty() && version.chars().all(|c| c.is_ascii_digit()) =>
+                {
+                    let major_len = if version.starts_with('1') { 2 } else { 1 };
+                    match version.split_at_checked(major_len) {
+                        Some((major, "")) => {
+                            vec![format!("Proton {major}.0"), format!("Proton {major}")]
+                        }
+                        Some((major, minor)) => vec![format!("Proton {major}.{minor}")],
+                        None => Vec::new(),
+                    }
+                }
+                _ => Vec::new(),
+            },
+        }
+    }
+
+    // the "version" file holds a build time and a name, e.g. "1718116216 proton-9.0-2"
+    fn read_proton_version(proton_dir: &Path) -> Option<String> {
+        fs::read_to_string(proton_dir.join("version"))
+            .ok()?
+            .split_whitespace()
+            .last()
+            .map(str::to_string)
+    }
+
+    #[inline]
+    pub fn steam_dir(&self) -> &Path {
+        &self.m_steam_dir
+    }
+
+    #[inline]
+    pub fn has_noita(&self) -> bool {
+        self.m_has_noita
+    }
+
+    // the command starting the client owning this Steam root, a sandboxed client has to be
+    // reached through its package manager
+    fn client_command(&self) -> Command {
+        let root = self.m_steam_dir.to_string_lossy();
+        if root.contains(FLATPAK_APP) {
+            let mut cmd = Command::new("flatpak");
+            cmd.args(["run", FLATPAK_APP]);
+            cmd
+        } else if root.contains(SNAP_APP) {
+            let mut cmd = Command::new("snap");
+            cmd.args(["run", "steam"]);
+            cmd
+        } else {
+            Command::new("steam")
+        }
+    }
+
+    // the shader cache sits next to "compatdata" in the same library
+    fn shader_cache(&self) -> PathBuf {
+        self.m_compat_data
+            .parent()
+            .and_then(Path::parent)
+            .map_or(self.m_steam_dir.join("steamapps"), Path::to_path_buf)
+            .join(format!("shadercache/{APPID}"))
+    }
+}
+
+impl Launcher for SteamLayout {
+    #[inline]
+    fn kind(&self) -> LauncherKind {
+        LauncherKind::Steam
+    }
+
+    #[inline]
+    fn live_save_path(&self) -> PathBuf {
+        self.m_compat_data.join(PREFIX_SAVE_PATH)
+    }
+
+    #[inline]
+    fn noita_dir(&self) -> PathBuf {
+        self.m_noita_dir.clone()
+    }
+
+    // runs Proton the way Steam would, but without the Steam client
+    fn launch(&self) -> NSComResult {
+        let proton_exe = self.m_proton_dir.join("proton");
+        let runsh = self.m_steam_dir.join("ubuntu12_32/steam-runtime/run.sh");
+
+        let mut cmd = if runsh.exists() {
+            let mut c = Command::new(&runsh);
+            c.arg(&proton_exe);
+            c
+        } else {
+            Command::new(&proton_exe)
+        };
+
+        cmd.arg("waitforexitandrun")
+            .arg(self.m_noita_dir.join("noita.exe"))
+            .current_dir(&self.m_noita_dir)
+            .env("STEAM_COMPAT_CLIENT_INSTALL_PATH", &self.m_steam_dir)
+            .env("STEAM_COMPAT_DATA_PATH", &self.m_compat_data)
+            .env("STEAM_COMPAT_SHADER_PATH", self.shader_cache())
+            .env("STEAM_COMPAT_TOOL_PATH", &self.m_proton_dir)
+            .env("STEAM_COMPAT_TOOL_PATHS", &self.m_proton_dir)
+            .env("STEAM_COMPAT_APP_ID", APPID)
+            .env("SteamAppId", APPID)
+            .env("SteamGameId", APPID)
+            .env("WINEPREFIX", self.m_compat_data.join("pfx"))
+            .stdout(File::create("/dev/null")?)
+            .stderr(File::create("/dev/null")?)
+            .spawn()?;
+        Ok(())
+    }
+
+    // the client hands the request to a running instance and exits, or becomes Steam itself
+    fn launch_with_client(&self) -> NSComResult {
+        let mut cmd = self.client_command();
+        let program = cmd.get_program().to_string_lossy().to_string();
+        cmd.args(["-applaunch", APPID])
+            .stdout(File::create("/dev/null")?)
+            .stderr(File::create("/dev/null")?)
+            .spawn()
+            .explain(&t!("fail_start_steam_client", program = program))?;
+        Ok(())
+    }
+
+    #[inline]
+    fn path_decisions(&self) -> Vec<PathDecision> {
+        self.m_decisions.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SteamLayout;
+
+    #[test]
+    fn official_tool_dirs() {
+        assert_eq!(
+            SteamLayout::official_tool_dirs("proton_9"),
+            ["Proton 9.0", "Proton 9"]
+        );
+        assert_eq!(
+            SteamLayout::official_tool_dirs("proton_513"),
+            ["Proton 5.13"]
+        );
+        assert_eq!(SteamLayout::official_tool_dirs("proton_63"), ["Proton 6.3"]);
+        assert_eq!(
+            SteamLayout::official_tool_dirs("proton_10"),
+            ["Proton 10.0", "Proton 10"]
+        );
+        assert_eq!(
+            SteamLayout::official_tool_dirs("proton_103"),
+            ["Proton 10.3"]
+        );
+        assert!(SteamLayout::official_tool_dirs("proton_1").is_empty());
+        assert!(SteamLayout::official_tool_dirs("GE-Proton9-20").is_empty());
+    }
+}
@@ -0,0 +1,246 @@
+use std::fs;
+use std::path::Path;
+
+use super::error::*;
+
+// Valve's KeyValues text format used by "libraryfolders.vdf", "config.vdf" and app manifests
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Vdf {
+    Value(String),
+    Block(Vec<(String, Vdf)>),
+}
+
+impl Vdf {
+    // None if the file doesn't exist
+    pub fn load(path: &Path) -> NSResult<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let text =
+            fs::read_to_string(path).explain(&t!("fail_read_vdf", path = format!("{path:?}")))?;
+        Self::parse(&text)
+            .map(Some)
+            .explain(&t!("fail_read_vdf", path = format!("{path:?}")))
+    }
+
+    // the whole text is taken as the content of a block
+    pub fn parse(text: &str) -> NSResult<Self> {
+        let mut tokens = Tokens { m_rest: text };
+        let root = Self::parse_block(&mut tokens)?;
+        if tokens.next()?.is_some() {
+            return throw(&t!("vdf_unbalanced_braces"));
+        }
+        Ok(root)
+    }
+
+    // stops at the closing brace or the end of the text, whichever the caller expects
+    fn parse_block(tokens: &mut Tokens) -> NSResult<Self> {
+        let mut entries = Vec::new();
+        while let Some(token) = tokens.peek()? {
+            let Token::Text(key) = token else {
+                return match token {
+                    Token::Close => Ok(Vdf::Block(entries)),
+                    _ => throw(&t!("vdf_unbalanced_braces")),
+                };
+            };
+            tokens.next()?;
+            let value = match tokens.next()? {
+                Some(Token::Text(value)) => Vdf::Value(value),
+                Some(Token::Open) => {
+                    let block = Self::parse_block(tokens)?;
+                    if tokens.next()? != Some(Token::Close) {
+                        return throw(&t!("vdf_unbalanced_braces"));
+                    }
+                    block
+                }
+                _ => return throw(&t!("vdf_missing_value", key = key)),
+            };
+            entries.push((key, value));
+        }
+        Ok(Vdf::Block(entries))
+    }
+
+    // keys are compared ignoring case like Steam does, e.g. "CompatToolMapping"
+    pub fn get(&self, key: &str) -> Option<&Vdf> {
+        self.entries()
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value)
+    }
+
+    // follows nested blocks, e.g. `["libraryfolders", "0", "path"]`
+    pub fn get_path(&self, keys: &[&str]) -> Option<&Vdf> {
+        keys.iter().try_fold(self, |node, key| node.get(key))
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Vdf::Value(value) => Some(value),
+            Vdf::Block(_) => None,
+        }
+    }
+
+    // empty for a plain value
+    #[inline]
+    pub fn entries(&self) -> &[(String, Vdf)] {
+        match self {
+            Vdf::Value(_) => &[],
+            Vdf::Block(entries) => entries,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Text(String),
+    Open,
+    Close,
+}
+
+struct Tokens<'a> {
+    m_rest: &'a str,
+}
+
+impl Tokens<'_> {
+    fn peek(&mut self) -> NSResult<Option<Token>> {
+        let rest = self.m_rest;
+        let token = self.next();
+        self.m_rest = rest;
+        token
+    }
+
+    fn next(&mut self) -> NSResult<Option<Token>> {
+        loop {
+            self.m_rest = self.m_rest.trim_start();
+            if let Some(comment) = self.m_rest.strip_prefix("//") {
+                self.m_rest = comment.split_once('\n').map_or("", |(_, rest)| rest);
+            } else if self.m_rest.starts_with('[') {
+                // platform conditions like "[$WIN32]", they don't change which key is meant
+                self.m_rest = self.m_rest.split_once(']').map_or("", |(_, rest)| rest);
+            } else {
+                break;
+            }
+        }
+        let mut chars = self.m_rest.char_indices();
+        let Some((_, first)) = chars.next() else {
+            return Ok(None);
+        };
+        match first {
+            '{' => {
+                self.m_rest = &self.m_rest[1..];
+                Ok(Some(Token::Open))
+            }
+            '}' => {
+                self.m_rest = &self.m_rest[1..];
+                Ok(Some(Token::Close))
+            }
+            '"' => {
+                let mut text = String::new();
+                let mut escaped = false;
+                for (index, c) in chars {
+                    if escaped {
+                        text.push(match c {
+                            'n' => '\n',
+                            't' => '\t',
+                            c => c,
+                        });
+                        escaped = false;
+                    } else if c == '\\' {
+                        escaped = true;
+                    } else if c == '"' {
+                        self.m_rest = &self.m_rest[index + 1..];
+                        return Ok(Some(Token::Text(text)));
+                    } else {
+                        text.push(c);
+                    }
+                }
+                throw(&t!("vdf_unterminated_string"))
+            }
+            _ => {
+                let end = self
+                    .m_rest
+                    .find(|c: char| c.is_whitespace() || c == '{' || c == '}' || c == '"')
+                    .unwrap_or(self.m_rest.len());
+                let text = self.m_rest[..end].to_string();
+                self.m_rest = &self.m_rest[end..];
+                Ok(Some(Token::Text(text)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Vdf;
+
+    fn value(text: &str) -> Vdf {
+        Vdf::Value(text.to_string())
+    }
+
+    #[test]
+    fn nested_blocks_and_case_insensitive_keys() {
+        let vdf = Vdf::parse(
+            r#"
+            "libraryfolders"
+            {
+                "0"
+                {
+                    "path"      "/home/user/.local/share/Steam"
+                    "apps" { "881100" "1234" }
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            vdf.get_path(&["LibraryFolders", "0", "path"]),
+            Some(&value("/home/user/.local/share/Steam"))
+        );
+        assert_eq!(
+            vdf.get_path(&["libraryfolders", "0", "apps", "881100"])
+                .and_then(Vdf::as_str),
+            Some("1234")
+        );
+        assert!(vdf.get_path(&["libraryfolders", "1"]).is_none());
+    }
+
+    #[test]
+    fn comments_and_unquoted_tokens() {
+        let vdf = Vdf::parse(
+            "// written by Steam\n\
+             \"config\" // the root\n\
+             {\n\
+                 key value // trailing\n\
+                 // \"ignored\" \"entry\"\n\
+             }",
+        )
+        .unwrap();
+        assert_eq!(vdf.get_path(&["config", "key"]), Some(&value("value")));
+        assert_eq!(vdf.get("config").unwrap().entries().len(), 1);
+    }
+
+    #[test]
+    fn platform_conditions_are_skipped() {
+        let vdf = Vdf::parse(r#""a" "1" [$WIN32] "b" { "c" "2" } [$LINUX]"#).unwrap();
+        assert_eq!(vdf.get("a"), Some(&value("1")));
+        assert_eq!(vdf.get_path(&["b", "c"]), Some(&value("2")));
+    }
+
+    #[test]
+    fn escapes() {
+        let vdf = Vdf::parse(r#""path" "C:\\Games\\Steam" "quote" "say \"hi\"\n""#).unwrap();
+        assert_eq!(vdf.get("path"), Some(&value(r"C:\Games\Steam")));
+        assert_eq!(vdf.get("quote"), Some(&value("say \"hi\"\n")));
+    }
+
+    #[test]
+    fn malformed_input_is_rejected() {
+        // unbalanced braces either way
+        assert!(Vdf::parse(r#""a" { "b" "c""#).is_err());
+        assert!(Vdf::parse(r#""a" { "b" "c" } }"#).is_err());
+        assert!(Vdf::parse("}").is_err());
+        // a key without a value and an unterminated string
+        assert!(Vdf::parse(r#""a" { "b" }"#).is_err());
+        assert!(Vdf::parse(r#""a" "b"#).is_err());
+    }
+}
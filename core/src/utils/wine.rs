@@ -0,0 +1,137 @@
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::error::*;
+use super::launcher::{Launcher, LauncherKind};
+use super::path_decision::PathDecision;
+use super::settings::Settings;
+
+const DEFAULT_WINE: &str = "wine";
+const USERS_DIR: &str = "drive_c/users";
+// below the profile folder of the Windows user inside the prefix
+const PROFILE_SAVE_PATH: &str = "AppData/LocalLow/Nolla_Games_Noita/save00";
+// what Wine falls back to if the Unix user can't be told
+const FALLBACK_USER: &str = "user";
+
+// runs "noita.exe" with a plain Wine in a prefix set by the user
+#[derive(Debug, Clone)]
+pub struct WineLauncher {
+    m_prefix: PathBuf,
+    m_exe: PathBuf,
+    m_wine: String,
+    m_user: String,
+    m_user_reason: String,
+}
+
+impl WineLauncher {
+    pub fn new(settings: &Settings) -> NSResult<Self> {
+        if settings.wine_prefix.is_empty() {
+            return throw(&t!("wine_prefix_not_set"));
+        }
+        if settings.wine_exe.is_empty() {
+            return throw(&t!("wine_exe_not_set"));
+        }
+        let prefix = PathBuf::from(&settings.wine_prefix);
+        let (user, user_reason) = Self::profile_user(&prefix);
+        Ok(Self {
+            m_prefix: prefix,
+            m_exe: PathBuf::from(&settings.wine_exe),
+            m_wine: if settings.wine_binary.is_empty() {
+                DEFAULT_WINE.to_string()
+            } else {
+                settings.wine_binary.clone()
+            },
+            m_user: user,
+            m_user_reason: user_reason,
+        })
+    }
+
+    // Wine names the profile folder after the Unix user, but a prefix made elsewhere, e.g. by
+    // Lutris on another machine, may already hold Noita's save under another name
+    fn profile_user(prefix: &Path) -> (String, String) {
+        if let Ok(entries) = fs::read_dir(prefix.join(USERS_DIR)) {
+            for entry in entries.flatten() {
+                let has_save = Path::new(PROFILE_SAVE_PATH)
+                    .parent()
+                    .is_some_and(|noita| entry.path().join(noita).is_dir());
+                if has_save {
+                    let user = entry.file_name().to_string_lossy().to_string();
+                    return (user, t!("wine_user_with_save").to_string());
+                }
+            }
+        }
+        match std::env::var("USER") {
+            Ok(user) if !user.is_empty() => (user, t!("wine_user_from_env").to_string()),
+            _ => (
+                FALLBACK_USER.to_string(),
+                t!("wine_user_fallback", user = FALLBACK_USER).to_string(),
+            ),
+        }
+    }
+}
+
+impl Launcher for WineLauncher {
+    #[inline]
+    fn kind(&self) -> LauncherKind {
+        LauncherKind::Wine
+    }
+
+    fn live_save_path(&self) -> PathBuf {
+        self.m_prefix
+            .join(USERS_DIR)
+            .join(&self.m_user)
+            .join(PROFILE_SAVE_PATH)
+    }
+
+    fn noita_dir(&self) -> PathBuf {
+        self.m_exe
+            .parent()
+            .map_or(self.m_exe.clone(), Path::to_path_buf)
+    }
+
+    fn launch(&self) -> NSComResult {
+        if !self.m_exe.is_file() {
+            return throw(&t!("wine_exe_not_found", path = self.m_exe.display()));
+        }
+        Command::new(&self.m_wine)
+            .arg(&self.m_exe)
+            .current_dir(self.noita_dir())
+            .env("WINEPREFIX", &self.m_prefix)
+            .stdout(File::create("/dev/null")?)
+            .stderr(File::create("/dev/null")?)
+            .spawn()
+            .explain(&t!("fail_start_wine", wine = self.m_wine))?;
+        Ok(())
+    }
+
+    fn path_decisions(&self) -> Vec<PathDecision> {
+        let wine_reason = if self.m_wine == DEFAULT_WINE {
+            t!("wine_binary_default")
+        } else {
+            t!("wine_binary_from_setting")
+        };
+        vec![
+            PathDecision::new(
+                t!("path_item_wine_prefix"),
+                self.m_prefix.is_dir().then(|| self.m_prefix.clone()),
+                t!("wine_prefix_from_setting"),
+            ),
+            PathDecision::new(
+                t!("path_item_noita"),
+                self.m_exe.is_file().then(|| self.m_exe.clone()),
+                t!("wine_exe_from_setting"),
+            ),
+            PathDecision::new(
+                t!("path_item_wine"),
+                Some(PathBuf::from(&self.m_wine)),
+                wine_reason,
+            ),
+            PathDecision::new(
+                t!("path_item_live_save"),
+                Some(self.live_save_path()),
+                self.m_user_reason.clone() + &format!(" ({})", self.m_user),
+            ),
+        ]
+    }
+}
@@ -1,8 +1,15 @@
 use super::{APP_HANDLE, CORE};
 use crate::gui_output::GuiOutput;
-use noita_save_manager_core::{Core, NSComResult, NSError, NSResult, SingleSave, throw};
-use std::sync::MutexGuard;
+use noita_save_manager_core::{
+    AutoSaveEvent, AutoSaveScheduler, Core, LaunchMode, LiveStatus, ModProfile, NSComResult,
+    NSError, NSResult, PathDecision, ProgressDiff, ProgressProfile, RestoreMode, RestorePlan,
+    SaveDiff, Settings, SingleSave, StatsReport, WandSummary, throw,
+};
+use serde::Serialize;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
 use tauri::Emitter;
+use tauri::ipc::Response;
 
 fn release_backend_lock() {
     APP_HANDLE
@@ -12,6 +19,33 @@ fn release_backend_lock() {
         .unwrap();
 }
 
+#[derive(Clone, Serialize)]
+pub struct LiveStatusPayload {
+    status: LiveStatus,
+    message: String,
+}
+
+impl LiveStatusPayload {
+    fn new(status: LiveStatus) -> Self {
+        Self {
+            message: status.to_string(),
+            status,
+        }
+    }
+}
+
+// lets the frontend refresh its "unsaved progress" indicator after anything that changes the
+// live save or the newest snapshot
+fn emit_live_status(core: &Core<GuiOutput>) {
+    if let Ok(status) = core.live_status() {
+        APP_HANDLE
+            .get()
+            .unwrap()
+            .emit("live_status", LiveStatusPayload::new(status))
+            .unwrap();
+    }
+}
+
 fn get_core<'a>() -> NSResult<MutexGuard<'a, Core<GuiOutput>>> {
     let Ok(core) = CORE
         .get()
@@ -30,6 +64,14 @@ pub fn get_locale() -> NSResult<String> {
     Ok(locale)
 }
 
+// `Core::new` runs before the app handle exists, so its warnings wait for the frontend to listen
+#[tauri::command]
+pub fn report_startup_warnings() -> NSComResult {
+    get_core()?.report_startup_warnings();
+    release_backend_lock();
+    Ok(())
+}
+
 #[tauri::command]
 pub fn get_saves() -> NSResult<Vec<SingleSave>> {
     let saves = get_core()?.get_save_infos().saves.clone();
@@ -38,9 +80,159 @@ pub fn get_saves() -> NSResult<Vec<SingleSave>> {
 }
 
 #[tauri::command]
-pub fn cmd_startgame() -> NSComResult {
+pub fn get_settings() -> NSResult<Settings> {
+    let settings = get_core()?.get_settings().clone();
+    release_backend_lock();
+    Ok(settings)
+}
+
+#[tauri::command]
+pub fn cmd_set_setting(key: String, value: String) -> NSComResult {
+    let mut core = get_core()?;
+    core.set_setting(&key, &value)?;
+    release_backend_lock();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_wands(index: usize) -> NSResult<Vec<WandSummary>> {
+    let wands = get_core()?.inspect_wands(index)?;
+    release_backend_lock();
+    Ok(wands)
+}
+
+#[tauri::command]
+pub fn find_saves_with_perk(perk: String) -> NSResult<Vec<usize>> {
+    let indexes = get_core()?.find_saves_with_perk(&perk);
+    release_backend_lock();
+    Ok(indexes)
+}
+
+#[tauri::command]
+pub fn find_saves_of_run(seed: u32) -> NSResult<Vec<usize>> {
+    let indexes = get_core()?.find_saves_of_run(seed);
+    release_backend_lock();
+    Ok(indexes)
+}
+
+#[tauri::command]
+pub fn get_runs() -> NSResult<Vec<(Option<u32>, Vec<usize>)>> {
+    let runs = get_core()?.runs();
+    release_backend_lock();
+    Ok(runs)
+}
+
+#[tauri::command]
+pub fn get_stats() -> NSResult<StatsReport> {
+    let report = get_core()?.stats()?;
+    release_backend_lock();
+    Ok(report)
+}
+
+// `old` or `new` left out stands for the live save
+#[tauri::command]
+pub fn cmd_diff(old: Option<usize>, new: Option<usize>) -> NSResult<SaveDiff> {
+    let diff = get_core()?.diff(old, new)?;
+    release_backend_lock();
+    Ok(diff)
+}
+
+// the PNG bytes of the save's minimap, empty if the save has no explored chunk
+#[tauri::command]
+pub fn get_minimap(index: usize) -> NSResult<Response> {
+    let path = get_core()?.minimap(index)?;
+    release_backend_lock();
+    let bytes = match path {
+        Some(path) => std::fs::read(path)?,
+        None => Vec::new(),
+    };
+    Ok(Response::new(bytes))
+}
+
+#[tauri::command]
+pub fn get_live_status() -> NSResult<LiveStatusPayload> {
+    let status = get_core()?.live_status()?;
+    release_backend_lock();
+    Ok(LiveStatusPayload::new(status))
+}
+
+#[tauri::command]
+pub fn get_mod_profiles() -> NSResult<Vec<ModProfile>> {
+    let profiles = get_core()?.get_mod_profiles().to_vec();
+    release_backend_lock();
+    Ok(profiles)
+}
+
+#[tauri::command]
+pub fn cmd_capture_mod_profile(name: String) -> NSComResult {
+    let mut core = get_core()?;
+    core.capture_mod_profile(name)?;
+    release_backend_lock();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn cmd_apply_mod_profile(name: String) -> NSComResult {
     let core = get_core()?;
-    core.startgame()?;
+    core.apply_mod_profile(&name)?;
+    emit_live_status(&core);
+    release_backend_lock();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn cmd_delete_mod_profile(name: String) -> NSComResult {
+    let mut core = get_core()?;
+    core.delete_mod_profile(&name)?;
+    release_backend_lock();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_progress_profiles() -> NSResult<Vec<ProgressProfile>> {
+    let profiles = get_core()?.get_progress_profiles().to_vec();
+    release_backend_lock();
+    Ok(profiles)
+}
+
+#[tauri::command]
+pub fn cmd_capture_progress_profile(name: String) -> NSComResult {
+    let mut core = get_core()?;
+    core.capture_progress_profile(name)?;
+    release_backend_lock();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn cmd_switch_progress_profile(name: String) -> NSComResult {
+    let core = get_core()?;
+    core.switch_progress_profile(&name)?;
+    emit_live_status(&core);
+    release_backend_lock();
+    Ok(())
+}
+
+// `old` or `new` left out stands for the live progress
+#[tauri::command]
+pub fn cmd_compare_progress(old: Option<String>, new: Option<String>) -> NSResult<ProgressDiff> {
+    let diff = get_core()?.compare_progress(old.as_deref(), new.as_deref())?;
+    release_backend_lock();
+    Ok(diff)
+}
+
+#[tauri::command]
+pub fn cmd_delete_progress_profile(name: String) -> NSComResult {
+    let mut core = get_core()?;
+    core.delete_progress_profile(&name)?;
+    release_backend_lock();
+    Ok(())
+}
+
+// `mode` left out uses the "launch_mode" setting
+#[tauri::command]
+pub fn cmd_startgame(mode: Option<LaunchMode>) -> NSComResult {
+    let core = get_core()?;
+    core.startgame(mode)?;
     release_backend_lock();
     Ok(())
 }
@@ -61,10 +253,19 @@ pub fn cmd_usage() -> NSResult<f64> {
     Ok(usage)
 }
 
+// every game path in use and why it was chosen
+#[tauri::command]
+pub fn get_path_diagnostics() -> NSResult<Vec<PathDecision>> {
+    let diagnostics = get_core()?.path_diagnostics();
+    release_backend_lock();
+    Ok(diagnostics)
+}
+
 #[tauri::command]
-pub fn cmd_save(name: String, note: String) -> NSComResult {
+pub fn cmd_save(name: String, note: String, with_shared: Option<bool>) -> NSComResult {
     let mut core = get_core()?;
-    core.save(name, note)?;
+    core.save(name, note, with_shared.unwrap_or(false))?;
+    emit_live_status(&core);
     release_backend_lock();
     Ok(())
 }
@@ -73,6 +274,7 @@ pub fn cmd_save(name: String, note: String) -> NSComResult {
 pub fn cmd_qsave() -> NSComResult {
     let mut core = get_core()?;
     core.quick_save(false)?;
+    emit_live_status(&core);
     release_backend_lock();
     Ok(())
 }
@@ -81,22 +283,76 @@ pub fn cmd_qsave() -> NSComResult {
 pub fn cmd_overwrite() -> NSComResult {
     let mut core = get_core()?;
     core.overwrite_save()?;
+    emit_live_status(&core);
     release_backend_lock();
     Ok(())
 }
 
+// the frontend only polls, `AutoSaveScheduler` decides when to save so the final snapshot after
+// the game exits is taken the same way as on the command line
+static AUTO_SAVE_SCHEDULER: Mutex<Option<AutoSaveScheduler>> = Mutex::new(None);
+
+// returns how often the frontend should call `cmd_autosave`, in milliseconds
 #[tauri::command]
-pub fn cmd_autosave(max_saves: usize) -> NSComResult {
+pub fn cmd_start_autosave(interval_mins: u64) -> NSResult<u64> {
+    let core = get_core()?;
+    let scheduler = core.auto_save_scheduler(Duration::from_secs(interval_mins * 60))?;
+    release_backend_lock();
+    let poll_interval = scheduler.poll_interval().as_millis() as u64;
+    *AUTO_SAVE_SCHEDULER.lock().unwrap() = Some(scheduler);
+    Ok(poll_interval)
+}
+
+#[tauri::command]
+pub fn cmd_stop_autosave() {
+    *AUTO_SAVE_SCHEDULER.lock().unwrap() = None;
+}
+
+#[tauri::command]
+pub fn cmd_autosave(max_saves: usize) -> NSResult<AutoSaveEvent> {
     let mut core = get_core()?;
-    core.auto_save(max_saves)?;
+    let event = match AUTO_SAVE_SCHEDULER.lock().unwrap().as_mut() {
+        Some(scheduler) => scheduler.tick(|| core.is_game_running().unwrap_or(false)),
+        None => AutoSaveEvent::Idle,
+    };
+    if matches!(event, AutoSaveEvent::Save | AutoSaveEvent::FinalSave) {
+        core.auto_save(max_saves)?;
+    }
+    emit_live_status(&core);
+    release_backend_lock();
+    Ok(event)
+}
+
+// the GUI's confirmation dialog, asked before `cmd_load` since `GuiOutput` can't confirm
+#[tauri::command]
+pub fn get_load_warnings(index: usize, mode: Option<RestoreMode>) -> NSResult<Vec<String>> {
+    let warnings = get_core()?.load_warnings(index, mode.unwrap_or_default())?;
+    release_backend_lock();
+    Ok(warnings)
+}
+
+// `mode` left out restores the whole save
+#[tauri::command]
+pub fn cmd_load(index: usize, mode: Option<RestoreMode>) -> NSComResult {
+    let core = get_core()?;
+    core.load_save(index, mode.unwrap_or_default())?;
+    emit_live_status(&core);
     release_backend_lock();
     Ok(())
 }
 
 #[tauri::command]
-pub fn cmd_load(index: usize) -> NSComResult {
+pub fn get_restore_plan(index: usize, mode: RestoreMode) -> NSResult<RestorePlan> {
+    let plan = get_core()?.restore_plan(index, mode)?;
+    release_backend_lock();
+    Ok(plan)
+}
+
+// restores only "save_shared", the slot is left untouched
+#[tauri::command]
+pub fn cmd_restore_shared_settings(index: usize) -> NSComResult {
     let core = get_core()?;
-    core.load_save(index)?;
+    core.restore_shared_settings(index)?;
     release_backend_lock();
     Ok(())
 }
@@ -105,6 +361,7 @@ pub fn cmd_load(index: usize) -> NSComResult {
 pub fn cmd_qload() -> NSComResult {
     let core = get_core()?;
     core.quick_load()?;
+    emit_live_status(&core);
     release_backend_lock();
     Ok(())
 }
@@ -113,6 +370,7 @@ pub fn cmd_qload() -> NSComResult {
 pub fn cmd_delete(indexs: Vec<usize>) -> NSComResult {
     let mut core = get_core()?;
     core.delete_saves(indexs)?;
+    emit_live_status(&core);
     release_backend_lock();
     Ok(())
 }
@@ -121,10 +379,21 @@ pub fn cmd_delete(indexs: Vec<usize>) -> NSComResult {
 pub fn cmd_qdelete() -> NSComResult {
     let mut core = get_core()?;
     core.quick_delete_save()?;
+    emit_live_status(&core);
     release_backend_lock();
     Ok(())
 }
 
+// with `dry_run` only reports the indexes the retention policies would remove
+#[tauri::command]
+pub fn cmd_prune(dry_run: bool) -> NSResult<Vec<usize>> {
+    let mut core = get_core()?;
+    let indexes = core.prune(dry_run)?;
+    emit_live_status(&core);
+    release_backend_lock();
+    Ok(indexes)
+}
+
 #[tauri::command]
 pub fn cmd_modify_lock(indexs: Vec<usize>, operate: bool) -> NSComResult {
     let mut core = get_core()?;
@@ -55,4 +55,8 @@ impl OutputManager for GuiOutput {
     fn confirm(&self, _msg: String) -> noita_save_manager_core::NSResult<bool> {
         Ok(true)
     }
+
+    fn can_confirm(&self) -> bool {
+        false
+    }
 }
@@ -33,25 +33,54 @@ fn main() {
         })
         .invoke_handler(tauri::generate_handler![
             get_locale,
+            report_startup_warnings,
             get_saves,
+            get_settings,
+            get_wands,
+            find_saves_with_perk,
+            find_saves_of_run,
+            get_runs,
+            get_stats,
+            cmd_diff,
+            get_live_status,
+            get_minimap,
+            cmd_set_setting,
             // Utils
             cmd_startgame,
             cmd_setpath,
             cmd_usage,
+            get_path_diagnostics,
             // Save
             cmd_save,
             cmd_qsave,
             cmd_overwrite,
+            cmd_start_autosave,
             cmd_autosave,
+            cmd_stop_autosave,
             // Load
+            get_load_warnings,
             cmd_load,
             cmd_qload,
+            get_restore_plan,
+            cmd_restore_shared_settings,
             // Delete
             cmd_delete,
             cmd_qdelete,
+            cmd_prune,
             // Modify
             cmd_modify_lock,
-            cmd_modify
+            cmd_modify,
+            // Mod profiles
+            get_mod_profiles,
+            cmd_capture_mod_profile,
+            cmd_apply_mod_profile,
+            cmd_delete_mod_profile,
+            // Progression profiles
+            get_progress_profiles,
+            cmd_capture_progress_profile,
+            cmd_switch_progress_profile,
+            cmd_compare_progress,
+            cmd_delete_progress_profile
         ])
         .plugin(tauri_plugin_opener::init())
         .run(tauri::generate_context!())
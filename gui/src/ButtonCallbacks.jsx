@@ -126,9 +126,10 @@ export default function useButtonCb() {
         }
     }
 
-    function cmd_startgame() {
+    // without a mode the "launch_mode" setting decides
+    function cmd_startgame(mode) {
         if (check_backend_state()) {
-            invoke("cmd_startgame").catch(error_handle);
+            invoke("cmd_startgame", { mode: mode ?? null }).catch(error_handle);
         }
     }
 
@@ -269,20 +270,35 @@ export default function useButtonCb() {
     }
 
     const [timer, setTimer] = useState(null);
-    function autosaveWaite(max_saves) {
-        if (check_backend_state()) {
-            pushMsg(t("message.autosaving_warning"), 2);
-            invoke("cmd_autosave", { maxSaves: max_saves })
-                .then(() => {
-                    update_save_infos();
-                    pushMsg(t("message.autosave_succeed"), 3);
-                })
-                .catch(error_handle);
-        } else {
-            setTimeout(autosaveWaite, 1000);
-            pushMsg(t("message.autosave_backend_occupied"), 4);
+    // the interval keeps calling the copy from its own render, so go through a ref to see the
+    // current backend state, a busy backend just skips this poll
+    const autosaveTick = useRef(null);
+    autosaveTick.current = (max_saves) => {
+        if (backendLocked) {
+            return;
         }
-    }
+        setBackendState(true);
+        invoke("cmd_autosave", { maxSaves: max_saves })
+            .then((event) => {
+                switch (event) {
+                    case "save":
+                        update_save_infos();
+                        pushMsg(t("message.autosave_succeed"), 3);
+                        break;
+                    case "final_save":
+                        update_save_infos();
+                        pushMsg(t("message.autosave_final"), 3);
+                        break;
+                    case "game_started":
+                        pushMsg(t("message.autosave_resumed"), 4);
+                        break;
+                    case "game_exited":
+                        pushMsg(t("message.autosave_paused"), 4);
+                        break;
+                }
+            })
+            .catch(error_handle);
+    };
     const autosave_interval = useRef(3);
     const max_saves = useRef(1);
     function cmd_autosave() {
@@ -303,23 +319,33 @@ export default function useButtonCb() {
                 <OkCancleKit
                     okCallback={() => {
                         if (timer != null) {
-                            clearTimeout(timer);
+                            clearInterval(timer);
+                            setTimer(null);
                             pushMsg(t("message.old_timer_closed"), 4);
                         }
-                        const new_timer = setInterval(
-                            () => {
-                                autosaveWaite(max_saves.current);
-                            },
-                            autosave_interval.current * 60 * 1000,
-                        );
-                        setTimer(new_timer);
-                        pushMsg(
-                            t("message.creat_autosave_task", {
-                                interval: autosave_interval.current,
-                                max_saves: max_saves.current,
-                            }),
-                            3,
-                        );
+                        if (!check_backend_state()) {
+                            return;
+                        }
+                        invoke("cmd_start_autosave", {
+                            intervalMins: autosave_interval.current,
+                        })
+                            .then((poll_interval) => {
+                                const saves = max_saves.current;
+                                setTimer(
+                                    setInterval(
+                                        () => autosaveTick.current(saves),
+                                        poll_interval,
+                                    ),
+                                );
+                                pushMsg(
+                                    t("message.creat_autosave_task", {
+                                        interval: autosave_interval.current,
+                                        max_saves: saves,
+                                    }),
+                                    3,
+                                );
+                            })
+                            .catch(error_handle);
                     }}
                 >
                     <button
@@ -327,7 +353,8 @@ export default function useButtonCb() {
                         style={{ width: "35%" }}
                         onClick={() => {
                             if (timer != null) {
-                                clearTimeout(timer);
+                                clearInterval(timer);
+                                invoke("cmd_stop_autosave");
                                 pushMsg(t("message.old_timer_closed"), 4);
                                 setTimer(null);
                             }
@@ -349,8 +376,31 @@ export default function useButtonCb() {
             pushMsg(t("message.choose_only_one_save"), 2);
         } else {
             if (check_backend_state()) {
-                invoke("cmd_load", { index: indexs[0] })
-                    .then(operation_success)
+                const index = indexs[0];
+                const load = () => {
+                    setBackendState(true);
+                    invoke("cmd_load", { index })
+                        .then(operation_success)
+                        .catch(error_handle);
+                };
+                // a different game build or different mods are worth a second thought
+                invoke("get_load_warnings", { index })
+                    .then((warnings) => {
+                        if (warnings.length == 0) {
+                            load();
+                            return;
+                        }
+                        enableQueryWindow(
+                            t("load_title"),
+                            <>
+                                <p style={{ whiteSpace: "pre-line" }}>
+                                    {warnings.join("\n")}
+                                </p>
+                                <p>{t("load_anyway")}</p>
+                                <OkCancleKit okCallback={load} />
+                            </>,
+                        );
+                    })
                     .catch(error_handle);
             }
         }
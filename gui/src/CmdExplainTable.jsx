@@ -7,6 +7,7 @@ export default function CmdExplainTable() {
 
     const commandKeys = [
         "startgame",
+        "startgame_steam",
         "setpath",
         "usage",
         "loghistory",
@@ -43,9 +43,12 @@ function CommandPane(props) {
                 className="button_container"
                 style={{ gridArea: "A" }}
             >
-                <button type="button" onClick={cmd_startgame}>
+                <button type="button" onClick={() => cmd_startgame()}>
                     {t("startgame")}
                 </button>
+                <button type="button" onClick={() => cmd_startgame("steam")}>
+                    {t("startgame_steam")}
+                </button>
                 <button type="button" onClick={cmd_setpath}>
                     {t("setpath")}
                 </button>
@@ -20,6 +20,8 @@ export function GlobalProvider({ children }) {
     });
     const [saveCheckboxState, setCheckboxState] = useState([]);
     const [backendLocked, setBackendState] = useState(false);
+    // { status: { m_status, ... }, message } describing the live save against the newest save
+    const [liveStatus, setLiveStatus] = useState(null);
     const { i18n } = useTranslation();
 
     function msgBoxDisappear(id, is_delete) {
@@ -85,12 +87,18 @@ export function GlobalProvider({ children }) {
         const unlistenBackendLog = listen("backend_log", (event) => {
             pushMsg(event.payload.message, event.payload.log_grade);
         });
+        unlistenBackendLog.then(() => invoke("report_startup_warnings"));
 
         const unlistenRelease = listen("release_backend_lock", () => {
             setBackendState(false);
         });
 
+        const unlistenLiveStatus = listen("live_status", (event) => {
+            setLiveStatus(event.payload);
+        });
+
         invoke("get_locale").then((locale) => i18n.changeLanguage(locale));
+        invoke("get_live_status").then(setLiveStatus);
 
         // React Strict Mode will cause the Components to be mounted twice
         // which will register two listener
@@ -98,6 +106,7 @@ export function GlobalProvider({ children }) {
         return () => {
             unlistenBackendLog.then((f) => f());
             unlistenRelease.then((f) => f());
+            unlistenLiveStatus.then((f) => f());
         };
     }, []);
     return (
@@ -136,6 +145,9 @@ export function GlobalProvider({ children }) {
                     backendLocked,
                     setBackendState,
                 },
+                live_status_utils: {
+                    liveStatus,
+                },
             }}
         >
             {children}
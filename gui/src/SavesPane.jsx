@@ -12,6 +12,7 @@ function SavesPane(props) {
         save_info_utils: { saveInfos },
         bkg_disability_utils: { isBkgDisabled },
         save_checkbox_utils: { saveCheckboxState, setCheckboxState },
+        live_status_utils: { liveStatus },
     } = getGlobals();
     const { t } = useTranslation("common");
 
@@ -38,6 +39,16 @@ function SavesPane(props) {
                         {t("savepane.title")}
                         <br />
                         {t("savepane.notification")}
+                        {liveStatus ? (
+                            <>
+                                <br />
+                                <span
+                                    className={`live_status_${liveStatus.status.m_status}`}
+                                >
+                                    {liveStatus.message}
+                                </span>
+                            </>
+                        ) : null}
                     </caption>
                     <colgroup>
                         <col style={{ width: "7%" }} />